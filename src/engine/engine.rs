@@ -7,20 +7,35 @@ use std::io::{self, Lines, StdinLock, Write};
 pub type UciInput<'a> = Lines<StdinLock<'a>>;
 
 use std::marker::PhantomData;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
 use anyhow::anyhow;
 use rand::seq::IndexedRandom;
 use rand::rng;
 
+use crate::engine::models::fen::FenError;
 use crate::engine::models::r#move::Move;
+use crate::engine::notation::pgn::Game;
 use crate::engine::movegen::generate_legal_moves;
-use crate::engine::search::evaluation::Evaluation;
-use crate::engine::{models::board::Chessboard, movegen::generate_moves, search::Search};
+use crate::engine::search::limits::SearchLimits;
+use crate::engine::search::time::DEFAULT_MOVE_OVERHEAD;
+use crate::engine::search::tt::DEFAULT_HASH_SIZE;
+use crate::engine::{models::board::Chessboard, movegen::generate_moves, search::{DEFAULT_THREADS, Search}};
 use crate::pause;
 
 /// `Not Connected` State for the engine.
 pub struct NotConnected;
 /// `Connected` State for the engine, meaning the uci protocol and connection has been established and validated.
 pub struct Connected;
+
+/// Maximum value of the `Move Overhead` option, in milliseconds.
+const MAX_MOVE_OVERHEAD: u64 = 5000;
+/// Maximum value of the `Threads` option.
+const MAX_THREADS: usize = 12;
+/// Maximum value of the `Hash` option, in megabytes.
+const MAX_HASH_SIZE: usize = 4096;
 /// This is the entry of our chess engine, which will be used to start the game using a chessboard
 /// 
 /// The engine will support:
@@ -31,9 +46,10 @@ pub struct Connected;
 /// 
 /// # Exemples
 /// ```rust
-/// use lib::engine::engine::Engine;
+/// use lib::engine::engine::EngineBuilder;
 /// 
-/// let mut engine = Engine::new();
+/// let mut engine = EngineBuilder::new().default_fen().search(3).build().unwrap();
+/// // engine.start_self_game(); // plays against itself until the game is over
 /// // let mut engine = engine.validate_uci_connection().unwrap();
 /// // engine.start_uci_game(); // connecting and playing against another player using the litchess bot bridge
 /// ```
@@ -41,10 +57,59 @@ pub struct Engine<State = NotConnected> {
     /// Internal chessboard used to play by the engine itself.
     chessboard: Chessboard,
     search: Search,
+    /// FEN of the position the current game started from.
+    start_fen: String,
+    /// Moves played on `chessboard` since `start_fen`, used to export the game.
+    moves: Vec<Move>,
+    /// Search running in the background during an UCI game, `search` being moved into it meanwhile.
+    worker: Option<SearchWorker>,
     /// State of the engine, refer to [NotConnected] and [Connected].
     state: PhantomData<State>
 }
 
+/// Search running on its own thread, so the UCI input is still read while searching.
+struct SearchWorker {
+    /// Thread running the search, giving the search back once `bestmove` is sent.
+    thread: JoinHandle<Search>,
+    /// Shared with the search, interrupts it.
+    stop: Arc<AtomicBool>,
+    /// Shared with the search, cleared on `ponderhit`.
+    pondering: Arc<AtomicBool>,
+}
+
+impl<State> Engine<State> {
+    /// Makes a move on the internal chessboard and records it in the game history.
+    fn play(&mut self, mv: &Move) {
+        self.chessboard.make(mv);
+        self.moves.push(mv.clone());
+    }
+
+    /// Starts a new game history from the current internal chessboard.
+    fn reset_history(&mut self) {
+        self.start_fen = self.chessboard.to_fen();
+        self.moves.clear();
+    }
+
+    /// Returns the game played so far by the engine.
+    pub fn game(&self) -> Game {
+        match Chessboard::from_fen(&self.start_fen) {
+            Ok(mut chessboard) => {
+                let mut game = Game::from_moves(&mut chessboard, &self.moves);
+                game.set_tag("Event", "chessengine game");
+                game.set_tag("White", "chessengine");
+                game.set_tag("Black", "chessengine");
+                game
+            },
+            Err(_) => Game::default(),
+        }
+    }
+
+    /// Returns the game played so far by the engine as PGN.
+    pub fn to_pgn(&self) -> String {
+        self.game().to_pgn()
+    }
+}
+
 impl Engine<NotConnected> {
     /// Validate the uci protocol and ready to listen to next uci commands after `uciok`.
     pub fn validate_uci_connection<'a>(self, input: &mut UciInput<'a>) -> anyhow::Result<Engine<Connected>> {
@@ -58,17 +123,26 @@ impl Engine<NotConnected> {
         
         println!("id name chessengine");
         println!("id author Jojo");
-        println!("option name Move Overhead type spin default 30 min 0 max 5000");
-        println!("option name Threads type spin default 4 min 1 max 12");
-        println!("option name Hash type spin default 512");
+        println!("option name Move Overhead type spin default {} min 0 max {}", DEFAULT_MOVE_OVERHEAD, MAX_MOVE_OVERHEAD);
+        println!("option name Threads type spin default {} min 1 max {}", DEFAULT_THREADS, MAX_THREADS);
+        println!("option name Hash type spin default {} min 1 max {}", DEFAULT_HASH_SIZE, MAX_HASH_SIZE);
         println!("option name SyzygyPath type string default './syzygy/'");
         println!("option name UCI_ShowWDL type check default true");
+        for name in ["NullMovePruning", "LateMoveReductions", "FutilityPruning", "ReverseFutilityPruning", "LateMovePruning"] {
+            println!("option name {} type check default true", name);
+        }
         println!("uciok");  
         io::stdout().flush()?; // IMPORTANT
 
+        let chessboard = Chessboard::new();
+        let mut search = Search::new(3);
+        search.print_info = true;
         Ok(Engine { 
-            chessboard: Chessboard::new(),
-            search: Search::new(3),
+            start_fen: chessboard.to_fen(),
+            chessboard,
+            search,
+            moves: Vec::new(),
+            worker: None,
             state: PhantomData::<Connected> 
         })
     }
@@ -77,12 +151,16 @@ impl Engine<NotConnected> {
     pub fn start_self_game(&mut self) {
         let mut turn_counter = 0;
         loop {    
-            let best_move = self.search.think(&mut self.chessboard);
+            let result = self.search.think(&mut self.chessboard);
     
-            if let Some(best_move) = best_move {
-                self.chessboard.make(&best_move);
+            if let Some(best_move) = result.best_move {
+                self.play(&best_move);
                 println!("chessboard:\n{}", self.chessboard);
-                pause(&format!("-------------- {} {} {}", turn_counter, best_move, Evaluation::evaluate(&mut self.chessboard)));
+                pause(&format!("-------------- {} {} {}", turn_counter, best_move, self.search.evaluate(&self.chessboard)));
+            }
+
+            if self.is_game_over() {
+                break;
             }
 
             turn_counter += 1;
@@ -108,11 +186,15 @@ impl Engine<NotConnected> {
             let random_move = moves.choose(&mut rng);
     
             if let Some(random_move) = random_move {
-                self.chessboard.make(&random_move);
+                self.play(random_move);
                 println!("chessboard:\n{}", self.chessboard);
                 pause(&format!("-------------- {}", turn_counter));
             }
 
+            if self.is_game_over() {
+                break;
+            }
+
             turn_counter += 1;
         }
     } 
@@ -122,15 +204,15 @@ impl Engine<NotConnected> {
         loop {
             //player always plays white, need to play uci
             println!("enter your uci encoded move:");
-            let uci = console::Term::stdout().read_line().unwrap();
-            let decoded_move = &Move::decode_uci(&uci, &self.chessboard).unwrap();
-            let possibles_moves = generate_legal_moves(&mut self.chessboard);
-
-            if possibles_moves.iter().any(|mv| mv.to_string() == decoded_move.to_string()) {
-                self.chessboard.make(decoded_move);
-            }
-            else {
-                continue;
+            let Ok(uci) = console::Term::stdout().read_line() else {
+                break;
+            };
+            match Move::decode_uci(&uci, &mut self.chessboard) {
+                Ok(decoded_move) => self.play(&decoded_move),
+                Err(err) => {
+                    println!("{}", err);
+                    continue;
+                }
             }
 
 
@@ -149,21 +231,35 @@ impl Engine<NotConnected> {
             let random_move = moves.choose(&mut rng);
     
             if let Some(random_move) = random_move {
-                self.chessboard.make(&random_move);
+                self.play(random_move);
                 println!("chessboard:\n{}", self.chessboard);
                 pause(&format!("-------------- {}", turn_counter));
             }
 
+            if self.is_game_over() {
+                break;
+            }
+
             turn_counter += 1;
         }
     }
+
+    /// Checks if the game is over using [Chessboard::outcome], and announces the result followed by the game as PGN.
+    fn is_game_over(&mut self) -> bool {
+        let outcome = self.chessboard.outcome();
+        if outcome.is_over() {
+            println!("{}", outcome);
+            println!("{}", self.to_pgn());
+        }
+        outcome.is_over()
+    }
 }
 
 impl Engine<Connected> {
     /// This method starts an UCI game, the engine or AI will return after each of its turn its corresponding "best move" as UCI encoding.
     pub fn start_uci_game<'a>(&mut self, input: &mut UciInput<'a>) -> anyhow::Result<()> {
-        let stdout = io::stdout();
-        let mut stdout = stdout.lock();
+        // not locked, the search worker writes `bestmove` on its own
+        let mut stdout = io::stdout();
 
         for line in input {
             let line = line?;
@@ -174,7 +270,18 @@ impl Engine<Connected> {
                     writeln!(stdout, "readyok")?;
                 }
                 "quit" => break,
+                "ucinewgame" => {
+                    self.stop_search();
+                    self.search.tt.clear();
+                }
+                "stop" => self.stop_search(),
+                "ponderhit" => {
+                    if let Some(worker) = &self.worker {
+                        worker.pondering.store(false, Ordering::Relaxed);
+                    }
+                }
                 cmd if cmd.starts_with("position") => {
+                    self.stop_search();
                     let mut parts = cmd.split_whitespace();
 
                     match parts.next() {
@@ -185,29 +292,48 @@ impl Engine<Connected> {
                     match parts.next() {
                         Some("startpos") => {
                             self.chessboard = Chessboard::new();
+                            self.reset_history();
                         }
                         Some("fen") => {
                             let fen: String = parts.by_ref().take(6).collect::<Vec<_>>().join(" ");
-                            self.chessboard = Chessboard::from_fen(&fen).unwrap();
+                            match Chessboard::from_fen(&fen) {
+                                Ok(chessboard) => {
+                                    self.chessboard = chessboard;
+                                    self.reset_history();
+                                },
+                                Err(err) => {
+                                    // INVALID FEN → REPORT AND IGNORE THE WHOLE COMMAND (never panic)
+                                    writeln!(stdout, "info string invalid fen '{}': {}", fen, err)?;
+                                    stdout.flush()?;
+                                    continue;
+                                }
+                            }
                         }
                         _ => {}
                     }
 
                     if let Some("moves") = parts.next() {
                         for mv in parts {
-                            if let Ok(mv) = Move::decode_uci(mv, &self.chessboard) {
-                                self.chessboard.make(&mv);
-                            } else {
-                                // INVALID MOVE → IGNORE (never panic)
+                            match Move::decode_uci(mv, &mut self.chessboard) {
+                                Ok(mv) => self.play(&mv),
+                                Err(err) => {
+                                    // INVALID MOVE → REPORT AND IGNORE THE REMAINING MOVES, THEY DEPEND ON IT (never panic)
+                                    writeln!(stdout, "info string {}", err)?;
+                                    break;
+                                }
                             }
                         }
                     }
                 }
 
+                cmd if cmd.starts_with("setoption") => {
+                    self.stop_search();
+                    self.set_option(cmd);
+                }
                 cmd if cmd.starts_with("go") => {
-                    if let Some(best_move) = self.search.think(&mut self.chessboard) {
-                        writeln!(stdout, "bestmove {}", best_move)?;
-                    }
+                    self.stop_search();
+                    self.search.limits = SearchLimits::parse(cmd);
+                    self.start_search();
                 }
                 _ => {
                     // IGNORE unknown commands (REQUIRED by UCI)
@@ -217,8 +343,95 @@ impl Engine<Connected> {
             stdout.flush()?;
         }
 
+        self.stop_search();
         Ok(())
-    }    
+    }
+
+    /// Starts searching the current position on a worker thread, which sends `bestmove` once done.
+    /// 
+    /// When pondering, `bestmove` is held back until `ponderhit` or `stop`, as required by UCI.
+    fn start_search(&mut self) {
+        let mut search = std::mem::take(&mut self.search);
+        let mut chessboard = self.chessboard.clone();
+
+        let stop = search.stop_flag();
+        let pondering = search.ponder_flag();
+        stop.store(false, Ordering::Relaxed);
+        pondering.store(search.limits.ponder, Ordering::Relaxed);
+
+        let worker_stop = Arc::clone(&stop);
+        let worker_pondering = Arc::clone(&pondering);
+        let thread = thread::spawn(move || {
+            let result = search.think(&mut chessboard);
+            while worker_pondering.load(Ordering::Relaxed) && !worker_stop.load(Ordering::Relaxed) {
+                thread::sleep(Duration::from_millis(1));
+            }
+
+            match (result.best_move, result.ponder_move) {
+                (Some(best_move), Some(ponder_move)) => println!("bestmove {} ponder {}", best_move, ponder_move),
+                (Some(best_move), None) => println!("bestmove {}", best_move),
+                // no legal move, the game is over
+                (None, _) => println!("bestmove 0000"),
+            }
+            let _ = io::stdout().flush();
+
+            search
+        });
+
+        self.worker = Some(SearchWorker { thread, stop, pondering });
+    }
+
+    /// Stops the running search if any, and waits for its `bestmove`.
+    fn stop_search(&mut self) {
+        if let Some(worker) = self.worker.take() {
+            worker.stop.store(true, Ordering::Relaxed);
+            if let Ok(search) = worker.thread.join() {
+                self.search = search;
+            }
+        }
+    }
+
+    /// Applies a `setoption name <id> [value <x>]` command, unknown options and invalid values are ignored.
+    fn set_option(&mut self, command: &str) {
+        let option = command.strip_prefix("setoption").unwrap_or(command).trim();
+        let option = option.strip_prefix("name").unwrap_or(option).trim();
+        let (name, value) = match option.split_once(" value ") {
+            Some((name, value)) => (name.trim(), value.trim()),
+            None => (option, ""),
+        };
+
+        // option names are case insensitive
+        match name.to_lowercase().as_str() {
+            "move overhead" => {
+                if let Ok(millis) = value.parse::<u64>() {
+                    self.search.move_overhead = Duration::from_millis(millis.min(MAX_MOVE_OVERHEAD));
+                }
+            }
+            "threads" => {
+                if let Ok(threads) = value.parse::<usize>() {
+                    self.search.threads = threads.clamp(1, MAX_THREADS);
+                }
+            }
+            "hash" => {
+                if let Ok(megabytes) = value.parse::<usize>() {
+                    self.search.set_hash_size(megabytes.clamp(1, MAX_HASH_SIZE));
+                }
+            }
+            switch @ ("nullmovepruning" | "latemovereductions" | "futilitypruning" | "reversefutilitypruning" | "latemovepruning") => {
+                if let Ok(enabled) = value.to_lowercase().parse::<bool>() {
+                    let selectivity = &mut self.search.selectivity;
+                    match switch {
+                        "nullmovepruning" => selectivity.null_move = enabled,
+                        "latemovereductions" => selectivity.late_move_reductions = enabled,
+                        "futilitypruning" => selectivity.futility = enabled,
+                        "reversefutilitypruning" => selectivity.reverse_futility = enabled,
+                        _ => selectivity.late_move_pruning = enabled,
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
 }
 
 pub struct EngineBuilder {
@@ -236,7 +449,10 @@ impl EngineBuilder {
         self
     }
 
-    pub fn from_fen(mut self, fen: &str) -> Result<Self, &str> {
+    /// Sets the starting position of the engine from `fen`, returning the builder to chain the other settings.
+    ///
+    /// Returns the [FenError] of [Chessboard::from_fen] if `fen` is not a valid position.
+    pub fn from_fen(mut self, fen: &str) -> Result<Self, FenError> {
         self.chessboard = Some(Chessboard::from_fen(fen)?);
         Ok(self)
     }
@@ -249,8 +465,11 @@ impl EngineBuilder {
     pub fn build(self, ) -> Result<Engine<NotConnected>, String> {
         if let Some(chessboard) = self.chessboard && let Some(search) = self.search {
             return Ok(Engine { 
+                start_fen: chessboard.to_fen(),
                 chessboard, 
                 search,
+                moves: Vec::new(),
+                worker: None,
                 state: PhantomData::<NotConnected>
             })
         }
@@ -10,5 +10,7 @@ pub mod search;
 pub use engine::Engine;
 /// Move generations module
 pub mod movegen;
+/// Game and position notations built on top of the models (PGN, EPD).
+pub mod notation;
 /// Internal magic bitboard implementation for efficient move generation.
 pub(crate) mod magic;
\ No newline at end of file
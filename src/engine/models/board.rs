@@ -3,11 +3,15 @@
 // #![warn(clippy::missing_docs_in_private_items)]
 // #![deny(clippy::unwrap_used, clippy::expect_used)]
 
-use std::{collections::HashMap};
 use std::fmt;
 use std::str::FromStr;
 use serde::Deserialize;
 use crate::engine::models::undo::Undo;
+use crate::engine::models::zobrist::Zobrist;
+use crate::engine::models::psqt::PieceSquareScore;
+use crate::engine::models::outcome::Outcome;
+use crate::engine::models::fen::{FenError, FenField};
+use crate::engine::movegen::generate_legal_moves;
 use crate::{engine::models::{r#move::{Move, MoveKind}, piece::{Bishop, King, Knight, Pawn, Piece, Rook, SuperPiece}, state::State}};
 
 /// Represents a board rank, or horizontal line. `A1..H1`
@@ -215,7 +219,7 @@ impl Board {
 
 #[allow(missing_docs)]
 #[allow(clippy::missing_docs_in_private_items)]
-#[derive(Debug, Clone, Copy, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq, Eq)]
 pub enum Square {
     A1 = 0, B1 = 1, C1 = 2, D1 = 3, E1 = 4, F1 = 5, G1 = 6, H1 = 7,
     A2 = 8, B2 = 9, C2 = 10, D2 = 11, E2 = 12, F2 = 13, G2 = 14, H2 = 15,
@@ -323,8 +327,14 @@ pub struct Chessboard {
     pub state: State,
     /// Used to keep track of all undo needed to restore the state in the unmake function.
     pub(crate) undo_stack: Box<[Undo; 8191]>,
+    /// Zobrist key of every position reached so far, indexed by ply like `undo_stack`, used to detect repetitions.
+    pub(crate) key_history: Box<[u64; 8192]>,
     /// Used to index the state_stack, representing the current ply, equivalent to a half-move.
-    pub(crate) ply_index: usize
+    pub(crate) ply_index: usize,
+    /// Material and piece-square score of the position, updated along with the pieces.
+    pub(crate) psqt: PieceSquareScore,
+    /// Zobrist key of the pawns alone, updated along with them, keying the pawn hash table of the evaluation.
+    pub(crate) pawn_key: u64,
 }
 
 impl Chessboard {
@@ -365,111 +375,228 @@ impl Chessboard {
         (None, Color::White)
     }
     
-    /// Chessboard's constructor initialized with a custom fen value.1
-    pub fn from_fen(fen: &str) -> Result<Self, &str> {
+    /// Chessboard's constructor initialized with a custom fen value.
+    /// 
+    /// The string must hold the 6 fields of a FEN, and describe a legal position, see [Chessboard::validate].
+    pub fn from_fen(fen: &str) -> Result<Self, FenError> {
         // Initialize variables
         let mut chessboard = Chessboard::default();
 
         // Logic
-        let parts: Vec<&str> = fen.split(" ").collect();
+        let parts: Vec<&str> = fen.split_ascii_whitespace().collect();
         if parts.len() != 6 {
-            return Err("Malformed FEN string.");
+            return Err(FenError::FieldCount { found: parts.len() });
         }
+        // Byte index of a field inside the fen, used to locate errors.
+        let offset = |part: &str| part.as_ptr() as usize - fen.as_ptr() as usize;
         
         // Raw values
         let positions_pieces = parts[0];
         let turn_color = match parts[1] {
             "w" => Color::White,
             "b" => Color::Black,
-            _ => return Err("Invalid turn color detected.")
+            value => return Err(FenError::InvalidValue { field: FenField::SideToMove, index: offset(parts[1]), value: value.to_owned() })
         };
         let castling_ability = parts[2];
-        let en_passant_square = parts[3].to_uppercase();
-        let half_moves = u32::from_str(parts[4]).map_err(|_| "Invalid half-moves detected.");
-        let full_moves = u32::from_str(parts[5]).map_err(|_| "Invalid full-moves detected.");
+        let en_passant_square = parts[3];
         
         // Save into state
         chessboard.state.turn_color = turn_color;
-        
-        match half_moves {
-            Ok(value) => {
-                chessboard.state.half_move_clock = value;
-            },
-            Err(err) => {
-                return Err(err);
-            }
-        }
-        match full_moves {
-            Ok(value) => {
-                // chessboard.state.full_move_number = value;
-            },
-            Err(err) => {
-                return Err(err);
+        for (field, part) in [(FenField::HalfMoveClock, parts[4]), (FenField::FullMoveNumber, parts[5])] {
+            let value = u32::from_str(part).map_err(|_| FenError::InvalidValue { field, index: offset(part), value: part.to_owned() })?;
+            match field {
+                FenField::HalfMoveClock => chessboard.state.half_move_clock = value,
+                _ => chessboard.state.full_move_number = value,
             }
         }
         
-        for x in castling_ability.chars() {
-            match x {
-                'K' => {
-                    chessboard.state.castling_right |= 1;
-                },
-                'Q' => {
-                    chessboard.state.castling_right |= 2;
-                },
-                'k' => {
-                    chessboard.state.castling_right |= 4;
-                },
-                'q' => {
-                    chessboard.state.castling_right |= 8;
-                },
-                _ => {}
+        if castling_ability != "-" {
+            for (i, x) in castling_ability.char_indices() {
+                let right = match x {
+                    'K' => 1,
+                    'Q' => 2,
+                    'k' => 4,
+                    'q' => 8,
+                    _ => 0
+                };
+                // Unknown letters and duplicated rights are both rejected
+                if right == 0 || chessboard.state.castling_right & right != 0 {
+                    return Err(FenError::InvalidCharacter { field: FenField::Castling, index: offset(castling_ability) + i, character: x });
+                }
+                chessboard.state.castling_right |= right;
             }
         }
         
         // Parse En Passant part
         chessboard.state.en_passant_square = None;
-        if let Ok(ep_square) = Square::from_str(&en_passant_square) {
+        if en_passant_square != "-" {
+            let ep_square = Square::from_str(en_passant_square)
+                .map_err(|_| FenError::InvalidValue { field: FenField::EnPassant, index: offset(en_passant_square), value: en_passant_square.to_owned() })?;
             chessboard.state.en_passant_square = Some(ep_square);
         }
 
-        // Parsing piece positions
-        let mut raw_piece_to_type: HashMap<char, (Color, Piece)> = HashMap::new();
-        raw_piece_to_type.insert('P',(Color::White, Piece::Pawn));
-        raw_piece_to_type.insert('N',(Color::White, Piece::Knight));
-        raw_piece_to_type.insert('B',(Color::White, Piece::Bishop));
-        raw_piece_to_type.insert('R',(Color::White, Piece::Rook));
-        raw_piece_to_type.insert('Q',(Color::White, Piece::Queen));
-        raw_piece_to_type.insert('K',(Color::White, Piece::King));
-
-        raw_piece_to_type.insert('p',(Color::Black, Piece::Pawn));
-        raw_piece_to_type.insert('n',(Color::Black, Piece::Knight));
-        raw_piece_to_type.insert('b',(Color::Black, Piece::Bishop));
-        raw_piece_to_type.insert('r',(Color::Black, Piece::Rook));
-        raw_piece_to_type.insert('q',(Color::Black, Piece::Queen));
-        raw_piece_to_type.insert('k',(Color::Black, Piece::King));
-
-        let ranks: Vec<&str> = positions_pieces.split("/").collect();
-        let mut overall_index_square = 0;
+        // Parsing piece positions, from rank 8 down to rank 1
+        let ranks: Vec<&str> = positions_pieces.split('/').collect();
         if ranks.len() != 8 {
-            return Err("Invalid position information detected.");
+            return Err(FenError::RankCount { found: ranks.len() });
         }
-        for rank in ranks {
-            for i in (0..rank.len()).rev() {
-                let letter = rank.chars().nth(i).expect("Out-of-bounds error when parsing rank.");
-                if letter.is_numeric() {
-                    let n_letter = letter.to_digit(10).unwrap();
-                    overall_index_square += n_letter;
-                } else if let Some((_color, _piece)) = raw_piece_to_type.get(&letter) {
-                    let index: usize = 63_usize - overall_index_square as usize;
-                    chessboard.toggle_piece(get_piece_index(*_color, *_piece), 1 << index, *_color, *_piece);
-                    overall_index_square += 1;
+        for (rank_index, rank) in ranks.iter().enumerate() {
+            let rank_number = 8 - rank_index;
+            let mut file = 0;
+            let mut previous_was_digit = false;
+            for (i, letter) in rank.char_indices() {
+                let index = offset(rank) + i;
+                if let Some(empty_squares) = letter.to_digit(10) {
+                    if !(1..=8).contains(&empty_squares) || previous_was_digit {
+                        return Err(FenError::InvalidCharacter { field: FenField::Placement, index, character: letter });
+                    }
+                    file += empty_squares as usize;
+                    previous_was_digit = true;
+                    continue;
+                }
+
+                let color = if letter.is_ascii_uppercase() { Color::White } else { Color::Black };
+                let piece = match letter.to_ascii_lowercase() {
+                    'p' => Piece::Pawn,
+                    'n' => Piece::Knight,
+                    'b' => Piece::Bishop,
+                    'r' => Piece::Rook,
+                    'q' => Piece::Queen,
+                    'k' => Piece::King,
+                    _ => return Err(FenError::InvalidCharacter { field: FenField::Placement, index, character: letter })
+                };
+                if file >= 8 {
+                    return Err(FenError::RankLength { rank: rank_number, index: offset(rank), squares: file + 1 });
                 }
+                let square = 1u64 << ((rank_number - 1) * 8 + file);
+                chessboard.toggle_piece(get_piece_index(color, piece), square, color, piece);
+                file += 1;
+                previous_was_digit = false;
+            }
+            if file != 8 {
+                return Err(FenError::RankLength { rank: rank_number, index: offset(rank), squares: file });
             }
         }
 
+        chessboard.validate()?;
+
+        chessboard.state.zobrist_key = Zobrist::compute_hash(&chessboard);
+        chessboard.key_history[chessboard.ply_index] = chessboard.state.zobrist_key;
+
         Ok(chessboard)
     }
 
+    /// Checks that the position is legal:
+    /// - each side has exactly one king,
+    /// - no pawn stands on the first or last rank,
+    /// - the side not to move is not in check,
+    /// - the en passant square can result from a double pawn push of the side not to move,
+    /// - each castling right has its king and rook on their starting squares.
+    pub fn validate(&mut self) -> Result<(), FenError> {
+        for color in [Color::White, Color::Black] {
+            let found = self.get_piece(color, Piece::King).count_ones();
+            if found != 1 {
+                return Err(FenError::KingCount { color, found });
+            }
+        }
+
+        let back_rank_pawns = (self.get_piece(Color::White, Piece::Pawn) | self.get_piece(Color::Black, Piece::Pawn))
+            & (Rank::Rank1.mask() | Rank::Rank8.mask());
+        if back_rank_pawns != 0 {
+            #[allow(clippy::unwrap_used, reason="Infallible, the index comes from a non empty bitboard")]
+            let square = Square::try_from(back_rank_pawns.trailing_zeros() as u64).unwrap();
+            return Err(FenError::PawnOnBackRank { square });
+        }
+
+        if self.is_in_check() {
+            return Err(FenError::OpponentInCheck { color: self.state.turn_color.swap() });
+        }
+
+        if let Some(square) = self.state.en_passant_square {
+            let target = square.bitboard();
+            // the pawn which just double pushed, and the square it came from
+            let (pushed_pawn, origin, rank) = match self.state.turn_color {
+                Color::White => (target >> 8, target << 8, Rank::Rank6),
+                Color::Black => (target << 8, target >> 8, Rank::Rank3),
+            };
+            let opponent_pawns = self.get_piece(self.state.turn_color.swap(), Piece::Pawn);
+            if target & rank.mask() == 0 || pushed_pawn & opponent_pawns == 0 || self.any_occupied_square(target | origin) {
+                return Err(FenError::ImpossibleEnPassant { square });
+            }
+        }
+
+        let castling_rights = [
+            (1, 'K', Color::White, Square::E1, Square::H1),
+            (2, 'Q', Color::White, Square::E1, Square::A1),
+            (4, 'k', Color::Black, Square::E8, Square::H8),
+            (8, 'q', Color::Black, Square::E8, Square::A8),
+        ];
+        for (right, letter, color, king, rook) in castling_rights {
+            if self.state.castling_right & right != 0
+                && (self.get_piece(color, Piece::King) & king.bitboard() == 0 || self.get_piece(color, Piece::Rook) & rook.bitboard() == 0) {
+                return Err(FenError::InvalidCastlingRight { right: letter });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Serializes the chessboard into a FEN string, the exact inverse of [Chessboard::from_fen].
+    /// 
+    /// ```rust
+    /// use lib::engine::models::board::Chessboard;
+    /// 
+    /// let fen = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+    /// assert_eq!(Chessboard::from_fen(fen).unwrap().to_fen(), fen);
+    /// ```
+    pub fn to_fen(&self) -> String {
+        let mut fen = String::new();
+
+        for rank in (0..8).rev() {
+            let mut empty_squares = 0;
+            for file in 0..8 {
+                match self.get_piece_at_square(rank * 8 + file) {
+                    (Some(piece), color) => {
+                        if empty_squares > 0 {
+                            fen.push_str(&empty_squares.to_string());
+                            empty_squares = 0;
+                        }
+                        let c = char::from(piece);
+                        fen.push(if color == Color::White { c.to_ascii_uppercase() } else { c });
+                    },
+                    (None, _) => empty_squares += 1,
+                }
+            }
+            if empty_squares > 0 {
+                fen.push_str(&empty_squares.to_string());
+            }
+            if rank > 0 {
+                fen.push('/');
+            }
+        }
+
+        fen.push_str(match self.state.turn_color {
+            Color::White => " w ",
+            Color::Black => " b ",
+        });
+
+        let castling: String = [
+            (self.state.can_white_king_castle(), 'K'),
+            (self.state.can_white_queen_castle(), 'Q'),
+            (self.state.can_black_king_castle(), 'k'),
+            (self.state.can_black_queen_castle(), 'q'),
+        ].iter().filter(|(allowed, _)| *allowed).map(|(_, c)| *c).collect();
+        fen.push_str(if castling.is_empty() { "-" } else { &castling });
+
+        match self.state.en_passant_square {
+            Some(square) => fen.push_str(&format!(" {:?}", square).to_lowercase()),
+            None => fen.push_str(" -"),
+        }
+
+        fen.push_str(&format!(" {} {}", self.state.half_move_clock, self.state.full_move_number));
+        fen
+    }
+
     /// Returns the bitboard corresponding to the searched piece.
     /// 
     /// For example, `self.get_piece(Color::White, Piece::Pawn)` returns the bitboard with all white pawns.
@@ -612,23 +739,40 @@ impl Chessboard {
     /// chessboard.toggle_piece(get_piece_index(Color::White, Piece::Pawn), Square::A2.bitboard(), Color::White, Piece::Pawn);
     /// ```
     #[inline(always)]
-    pub fn slide_piece(&mut self, piece_index: usize, from: u64, to: u64, side: Color, _piece: Piece) {
+    pub fn slide_piece(&mut self, piece_index: usize, from: u64, to: u64, side: Color, piece: Piece) {
         let xor = from ^ to;
         self.pieces[piece_index] ^= xor;
         unsafe {
             let color_pieces = (&mut self.white_pieces as *mut u64).offset(side as isize);
             *color_pieces ^= xor;
         }
+        self.state.zobrist_key ^= Zobrist::piece_square(side, piece, from.trailing_zeros() as u8)
+            ^ Zobrist::piece_square(side, piece, to.trailing_zeros() as u8);
+        self.psqt.remove(side, piece, from.trailing_zeros());
+        self.psqt.add(side, piece, to.trailing_zeros());
+        if piece == Piece::Pawn {
+            self.pawn_key ^= Zobrist::piece_square(side, piece, from.trailing_zeros() as u8)
+                ^ Zobrist::piece_square(side, piece, to.trailing_zeros() as u8);
+        }
     }
 
     /// Use this method when required to put a piece without moving one or removing a piece, like during game initialization, captures or promotions.
     #[inline(always)]
-    pub fn toggle_piece(&mut self, piece_index: usize, square: u64, side: Color, _piece: Piece) {
+    pub fn toggle_piece(&mut self, piece_index: usize, square: u64, side: Color, piece: Piece) {
         self.pieces[piece_index] ^= square;
         unsafe {
             let color_pieces = (&mut self.white_pieces as *mut u64).offset(side as isize);
             *color_pieces ^= square;
         }
+        self.state.zobrist_key ^= Zobrist::piece_square(side, piece, square.trailing_zeros() as u8);
+        if self.pieces[piece_index] & square != 0 {
+            self.psqt.add(side, piece, square.trailing_zeros());
+        } else {
+            self.psqt.remove(side, piece, square.trailing_zeros());
+        }
+        if piece == Piece::Pawn {
+            self.pawn_key ^= Zobrist::piece_square(side, piece, square.trailing_zeros() as u8);
+        }
     }
 
     /// Make a move on the chessboard itself.
@@ -642,8 +786,13 @@ impl Chessboard {
             castling_right: self.state.castling_right,
             half_move_clock: self.state.half_move_clock,
             en_passant_square: self.state.en_passant_square,
+            zobrist_key: self.state.zobrist_key,
         };
 
+        // Remove the castling rights and en passant square from the key, they are hashed back once updated.
+        self.state.zobrist_key ^= Zobrist::castling(self.state.castling_right);
+        self.state.zobrist_key ^= Zobrist::en_passant_if_capturable(self, self.state.turn_color);
+
         // =====================
         // CASTLING
         // =====================
@@ -723,6 +872,7 @@ impl Chessboard {
             }
 
             self.state.en_passant_square = None;
+            self.state.half_move_clock += 1;
         }
 
         // =====================
@@ -749,6 +899,9 @@ impl Chessboard {
                 self.state.turn_color,
                 Piece::Pawn,
             );
+
+            self.state.en_passant_square = None;
+            self.state.half_move_clock = 0;
         }
 
         // =====================
@@ -977,10 +1130,20 @@ impl Chessboard {
             }
         }
 
-        // TODO: implement Zobrist
+        self.state.zobrist_key ^= Zobrist::castling(self.state.castling_right);
+        self.state.zobrist_key ^= Zobrist::en_passant_if_capturable(self, self.state.turn_color.swap());
+        self.state.zobrist_key ^= Zobrist::black_to_move();
+
+        if self.state.turn_color == Color::Black {
+            self.state.full_move_number += 1;
+        }
         self.state.turn_color = self.state.turn_color.swap();
         self.undo_stack[self.ply_index] = undo;
         self.ply_index += 1;
+        self.key_history[self.ply_index] = self.state.zobrist_key;
+
+        #[cfg(debug_assertions)]
+        self.debug_check_incremental();
     }
     
     /// Unmake a move on the chessboard itself.
@@ -988,6 +1151,9 @@ impl Chessboard {
         self.ply_index -= 1;
         let undo = self.undo_stack[self.ply_index];
         self.state.turn_color = self.state.turn_color.swap();
+        if self.state.turn_color == Color::Black {
+            self.state.full_move_number -= 1;
+        }
         
         // Restore castling rights, half move clock, and en passant from undo
         self.state.castling_right = undo.castling_right;
@@ -1052,6 +1218,172 @@ impl Chessboard {
 
         // Revert en passant square in any cases
         self.state.en_passant_square = undo.en_passant_square;
+        // The piece toggles above already reverted the piece keys, the saved key restores everything else.
+        self.state.zobrist_key = undo.zobrist_key;
+
+        #[cfg(debug_assertions)]
+        self.debug_check_incremental();
+    }
+
+    /// Passes the turn without moving, as the null-move pruning of the search does.
+    /// 
+    /// The en passant square is cleared and the half move clock reset, so that no position reached before the
+    /// null move counts as a repetition of one reached after it.
+    pub fn make_null(&mut self) {
+        let undo = Undo {
+            captured_piece: None,
+            castling_right: self.state.castling_right,
+            half_move_clock: self.state.half_move_clock,
+            en_passant_square: self.state.en_passant_square,
+            zobrist_key: self.state.zobrist_key,
+        };
+
+        self.state.zobrist_key ^= Zobrist::en_passant_if_capturable(self, self.state.turn_color);
+        self.state.zobrist_key ^= Zobrist::black_to_move();
+        self.state.en_passant_square = None;
+        self.state.half_move_clock = 0;
+
+        if self.state.turn_color == Color::Black {
+            self.state.full_move_number += 1;
+        }
+        self.state.turn_color = self.state.turn_color.swap();
+        self.undo_stack[self.ply_index] = undo;
+        self.ply_index += 1;
+        self.key_history[self.ply_index] = self.state.zobrist_key;
+
+        #[cfg(debug_assertions)]
+        self.debug_check_incremental();
+    }
+
+    /// Takes back a null move made by [Chessboard::make_null].
+    pub fn unmake_null(&mut self) {
+        self.ply_index -= 1;
+        let undo = self.undo_stack[self.ply_index];
+        self.state.turn_color = self.state.turn_color.swap();
+        if self.state.turn_color == Color::Black {
+            self.state.full_move_number -= 1;
+        }
+
+        self.state.half_move_clock = undo.half_move_clock;
+        self.state.en_passant_square = undo.en_passant_square;
+        self.state.zobrist_key = undo.zobrist_key;
+    }
+
+    /// Returns the zobrist key of the current position.
+    #[inline]
+    pub fn zobrist_key(&self) -> u64 {
+        self.state.zobrist_key
+    }
+
+    /// Returns how many times the current position already occurred before, with the same side to move.
+    /// 
+    /// Only the positions since the last capture or pawn move are looked at, since none before can be repeated.
+    pub fn repetition_count(&self) -> usize {
+        let key = self.state.zobrist_key;
+        let lookback = (self.state.half_move_clock as usize).min(self.ply_index);
+        (2..=lookback)
+            .step_by(2)
+            .filter(|distance| self.key_history[self.ply_index - distance] == key)
+            .count()
+    }
+
+    /// Checks if the current position already occurred before.
+    /// 
+    /// This is what the search uses, a single repetition is enough to score a line as a draw.
+    #[inline]
+    pub fn is_repetition(&self) -> bool {
+        self.repetition_count() >= 1
+    }
+
+    /// Checks if the current position occurred for the third time, which ends the game as a draw.
+    #[inline]
+    pub fn is_threefold_repetition(&self) -> bool {
+        self.repetition_count() >= 2
+    }
+
+    /// Checks if 50 moves (100 plies) have been played by both sides without any capture or pawn move.
+    #[inline]
+    pub fn is_fifty_move_draw(&self) -> bool {
+        self.state.half_move_clock >= 100
+    }
+
+    /// Checks if neither side has enough material left to checkmate: bare kings, a single minor piece,
+    /// or only bishops all standing on squares of the same color.
+    pub fn is_insufficient_material(&self) -> bool {
+        let mut heavy_pieces = 0u64;
+        let mut knights = 0u64;
+        let mut bishops = 0u64;
+        for color in [Color::White, Color::Black] {
+            heavy_pieces |= self.get_piece(color, Piece::Pawn) | self.get_piece(color, Piece::Rook) | self.get_piece(color, Piece::Queen);
+            knights |= self.get_piece(color, Piece::Knight);
+            bishops |= self.get_piece(color, Piece::Bishop);
+        }
+
+        if heavy_pieces != 0 {
+            return false;
+        }
+        if (knights | bishops).count_ones() <= 1 {
+            return true;
+        }
+
+        /// Bitboard of the light squares (`b1`, `a2`, ...).
+        const LIGHT_SQUARES: u64 = 0x55AA55AA55AA55AA;
+        knights == 0 && (bishops & LIGHT_SQUARES == 0 || bishops & !LIGHT_SQUARES == 0)
+    }
+
+    /// Returns the [Outcome] of the game in the current position.
+    /// 
+    /// Checkmate and stalemate are looked at first, so a mate delivered on the 100th ply still wins the game.
+    pub fn outcome(&mut self) -> Outcome {
+        if generate_legal_moves(self).is_empty() {
+            return if self.in_check() {
+                Outcome::Checkmate { winner: self.state.turn_color.swap() }
+            } else {
+                Outcome::Stalemate
+            };
+        }
+
+        if self.is_insufficient_material() {
+            Outcome::InsufficientMaterial
+        } else if self.is_fifty_move_draw() {
+            Outcome::FiftyMoveRule
+        } else if self.is_threefold_repetition() {
+            Outcome::ThreefoldRepetition
+        } else {
+            Outcome::Ongoing
+        }
+    }
+
+    /// Panics if the incrementally updated zobrist keys or piece-square score differ from a full recomputation.
+    #[cfg(debug_assertions)]
+    fn debug_check_incremental(&self) {
+        debug_assert_eq!(
+            self.state.zobrist_key,
+            Zobrist::compute_hash(self),
+            "incremental zobrist key diverged from a full recomputation"
+        );
+        debug_assert_eq!(
+            self.psqt,
+            PieceSquareScore::compute(self),
+            "incremental piece-square score diverged from a full recomputation"
+        );
+        debug_assert_eq!(
+            self.pawn_key,
+            Zobrist::compute_pawn_hash(self),
+            "incremental pawn key diverged from a full recomputation"
+        );
+    }
+
+    /// Returns the material and piece-square score of the position, from white's point of view.
+    #[inline]
+    pub fn psqt(&self) -> PieceSquareScore {
+        self.psqt
+    }
+
+    /// Returns the zobrist key of the pawns of the position, whatever the other pieces and the side to move.
+    #[inline]
+    pub fn pawn_key(&self) -> u64 {
+        self.pawn_key
     }
     
     /// Checks if the side that just moved is leaving their king in check (illegal move check)
@@ -1062,6 +1394,13 @@ impl Chessboard {
         let king = self.get_piece(side, Piece::King);
         self.is_square_attacked_by_color(king, side.swap())
     }
+
+    /// Checks if the side to move is currently in check.
+    pub fn in_check(&self) -> bool {
+        let side = self.state.turn_color;
+        let king = self.get_piece(side, Piece::King);
+        self.is_square_attacked_by_color(king, side.swap())
+    }
 }
 
 impl Default for Chessboard {
@@ -1072,7 +1411,10 @@ impl Default for Chessboard {
             black_pieces: 0u64,
             state: State::default(),
             undo_stack: Box::new([Undo::default(); 8191]),
+            key_history: Box::new([0; 8192]),
             ply_index: 0,
+            psqt: PieceSquareScore::default(),
+            pawn_key: 0,
         }
     }
 }
@@ -0,0 +1,134 @@
+#![warn(missing_docs, dead_code)]
+#![deny(unused_imports, unused_mut)]
+#![warn(clippy::missing_docs_in_private_items)]
+#![deny(clippy::unwrap_used, clippy::expect_used)]
+
+use std::fmt;
+
+use crate::engine::models::board::{Color, Square};
+
+/// The six space separated fields of a FEN string.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FenField {
+    /// Piece placement, from rank 8 to rank 1.
+    Placement,
+    /// Active color, `w` or `b`.
+    SideToMove,
+    /// Castling availability, `KQkq` or `-`.
+    Castling,
+    /// En passant target square or `-`.
+    EnPassant,
+    /// Number of plies since the last capture or pawn move.
+    HalfMoveClock,
+    /// Number of the full move.
+    FullMoveNumber,
+}
+
+impl FenField {
+    /// Every field, in the order they appear in a FEN string.
+    pub const ALL: [FenField; 6] = [
+        FenField::Placement,
+        FenField::SideToMove,
+        FenField::Castling,
+        FenField::EnPassant,
+        FenField::HalfMoveClock,
+        FenField::FullMoveNumber,
+    ];
+}
+
+/// Error returned by [crate::engine::models::board::Chessboard::from_fen].
+/// 
+/// Syntax errors carry the field and the byte `index` of the offending character in the whole FEN string,
+/// the other variants describe a well formed FEN which is not a legal chess position.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FenError {
+    /// The FEN does not have exactly 6 fields.
+    FieldCount {
+        /// Number of fields found.
+        found: usize
+    },
+    /// A character is not allowed in this field.
+    InvalidCharacter {
+        /// Field containing the character.
+        field: FenField,
+        /// Byte index of the character in the FEN string.
+        index: usize,
+        /// The offending character.
+        character: char
+    },
+    /// The placement does not describe exactly 8 ranks.
+    RankCount {
+        /// Number of ranks found.
+        found: usize
+    },
+    /// A rank does not describe exactly 8 squares.
+    RankLength {
+        /// Rank number, from 1 to 8.
+        rank: usize,
+        /// Byte index of the rank in the FEN string.
+        index: usize,
+        /// Number of squares described by the rank.
+        squares: usize
+    },
+    /// A field holds an invalid value, like a clock which is not a number.
+    InvalidValue {
+        /// Field containing the value.
+        field: FenField,
+        /// Byte index of the value in the FEN string.
+        index: usize,
+        /// The offending value.
+        value: String
+    },
+    /// A side does not have exactly one king.
+    KingCount {
+        /// Side with a wrong number of kings.
+        color: Color,
+        /// Number of kings found.
+        found: u32
+    },
+    /// A pawn stands on the first or last rank.
+    PawnOnBackRank {
+        /// Square of the pawn.
+        square: Square
+    },
+    /// The side not to move is in check, so its king could be captured.
+    OpponentInCheck {
+        /// Side in check while not to move.
+        color: Color
+    },
+    /// No double pawn push can have led to this en passant square.
+    ImpossibleEnPassant {
+        /// The en passant square.
+        square: Square
+    },
+    /// A castling right is set while the king or the rook left its starting square.
+    InvalidCastlingRight {
+        /// The castling right, as written in the FEN.
+        right: char
+    },
+}
+
+impl fmt::Display for FenError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FenError::FieldCount { found } => write!(f, "expected 6 fields, found {}", found),
+            FenError::InvalidCharacter { field, index, character } => {
+                write!(f, "invalid character '{}' in {:?} at index {}", character, field, index)
+            },
+            FenError::RankCount { found } => write!(f, "expected 8 ranks, found {}", found),
+            FenError::RankLength { rank, index, squares } => {
+                write!(f, "rank {} at index {} describes {} squares instead of 8", rank, index, squares)
+            },
+            FenError::InvalidValue { field, index, value } => write!(f, "invalid {:?} '{}' at index {}", field, value, index),
+            FenError::KingCount { color, found } => write!(f, "{:?} has {} kings instead of 1", color, found),
+            FenError::PawnOnBackRank { square } => write!(f, "pawn on back rank square {:?}", square),
+            FenError::OpponentInCheck { color } => write!(f, "{:?} is in check but it is not its turn", color),
+            FenError::ImpossibleEnPassant { square } => write!(f, "impossible en passant square {:?}", square),
+            FenError::InvalidCastlingRight { right } => {
+                write!(f, "castling right '{}' does not match the king and rook placement", right)
+            },
+        }
+    }
+}
+
+impl std::error::Error for FenError {}
@@ -6,5 +6,12 @@ pub mod state;
 pub mod board;
 /// Move generation and validation.
 pub mod r#move;
-mod zobrist;
-pub mod undo;
\ No newline at end of file
+/// Zobrist hashing of positions.
+pub mod zobrist;
+/// Tapered material and piece-square tables, kept up to date incrementally.
+pub mod psqt;
+pub mod undo;
+/// Result of a game.
+pub mod outcome;
+/// FEN parsing errors.
+pub mod fen;
\ No newline at end of file
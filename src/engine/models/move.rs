@@ -4,8 +4,33 @@
 #![deny(clippy::unwrap_used, clippy::expect_used)]
 
 use core::fmt;
+use std::str::FromStr;
 
-use crate::engine::models::{board::{Chessboard, Square, get_piece_index_raw}, piece::{Pawn, Piece}};
+use crate::engine::models::{board::{Chessboard, Square}, piece::{Pawn, Piece}};
+use crate::engine::movegen::generate_legal_moves;
+
+/// Error returned when a move written in a text notation can't be played on a chessboard.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MoveError {
+    /// The text is not a valid move in the expected notation.
+    Malformed(String),
+    /// The move is well formed, but not legal in the position.
+    Illegal(String),
+    /// Several legal moves match the text, it needs more disambiguation.
+    Ambiguous(String),
+}
+
+impl fmt::Display for MoveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MoveError::Malformed(mv) => write!(f, "malformed move '{}'", mv),
+            MoveError::Illegal(mv) => write!(f, "illegal move '{}'", mv),
+            MoveError::Ambiguous(mv) => write!(f, "ambiguous move '{}'", mv),
+        }
+    }
+}
+
+impl std::error::Error for MoveError {}
 
 /// Quick enum to match move kinds
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -120,57 +145,6 @@ impl Move {
         (self.word & 0b1111) as u8
     }
 
-    /// oui
-    pub(crate) fn uci_move_kind_code(from: u64, to: u64, chessboard: &Chessboard, promotion_char: Option<char>) -> u8 {
-        let to_square = Square::try_from(to.trailing_zeros() as u64).unwrap();
-
-        let mut smc = if let Some(character) = promotion_char {
-            Pawn::get_promotion_map()[&character]
-        }
-        else {
-            MoveKind::QuietMoves as u8
-        };
-
-        for piece_type_index in 0..6 {
-            if (chessboard.pieces[get_piece_index_raw(chessboard.state.turn_color, piece_type_index)] & from) != 0 {
-                if to & chessboard.get_all_pieces() != 0 {
-                    smc = MoveKind::Captures as u8;
-                    if let Some(character) = promotion_char {
-                        smc += Pawn::get_promotion_map()[&character]
-                    }
-                }
-                else {
-                    match Piece::try_from(piece_type_index as i32).unwrap() {
-                        Piece::Pawn => {
-                            let distance = from.trailing_zeros().abs_diff(to.trailing_zeros());
-                            if (distance == 7 || distance == 9) && ((to & chessboard.get_all_pieces()) != 0) {
-                                smc = MoveKind::EpCapture as u8;
-                            }
-                            break;
-                        },
-                        Piece::King => {
-                            if from.trailing_zeros().abs_diff(to.trailing_zeros()) == 2 {
-                                if to_square == Square::C1 || to_square == Square::C8 {
-                                    smc = MoveKind::QueenCastle as u8;
-                                }
-                                else if to_square == Square::G1 || to_square == Square::G8 {
-                                    smc = MoveKind::KingCastle as u8;
-                                }
-                            }
-                            break;
-                        },
-                        _ => {
-    
-                        }
-                    }
-                }
-                break;
-            }
-        }
-
-        smc 
-    }
-
     /// Returns the [MoveKind] of the move.
     pub(crate) fn move_kind(&self) -> MoveKind {
         #[allow(clippy::unwrap_used, reason="Infallible")]
@@ -195,61 +169,203 @@ impl Move {
         (self.word & 0b1000) != 0
     }
     
-    pub(crate) fn get_ucis() -> Vec<String> {
-        todo!()
-    }
-    
-    /// Decodes incoming uci encoded move into a `Move` object.
-    pub fn decode_uci(uci_move: &str, chessboard: &Chessboard) -> Result<Move, String> {
-        if uci_move.len() < 4 {
-            return Err("UCI move must be at least 4 characters".to_string());
+    /// Returns the piece a pawn promotes to, if the move is a promotion.
+    pub(crate) fn promotion_piece(&self) -> Option<Piece> {
+        if !self.promotion_flag() {
+            return None;
         }
-        
-        let promotion_char = if uci_move.len() == 5 {
-            Some(uci_move.chars().nth(4).ok_or("Invalid promotion character")?)
-        } else {
-            None
-        };
-        
-        let chars: Vec<char> = uci_move.chars().collect();
-        
-        // Parse from square
-        let from_file = (chars[0] as u8).checked_sub(b'a').ok_or("Invalid from file")?;
-        let from_rank = (chars[1] as u8).checked_sub(b'1').ok_or("Invalid from rank")?;
-        let from_square = (from_rank * 8 + from_file) as u16;
-        
-        // Parse to square
-        let to_file = (chars[2] as u8).checked_sub(b'a').ok_or("Invalid to file")?;
-        let to_rank = (chars[3] as u8).checked_sub(b'1').ok_or("Invalid to rank")?;
-        let to_square = (to_rank * 8 + to_file) as u16;
-        
-        let from_bitboard = 1u64 << from_square;
-        let to_bitboard = 1u64 << to_square;
-        
-        // Build the move word
-        let mut word = (from_square << 10) | (to_square << 4);
-        
-        // Determine the special move code
-        word |= Self::uci_move_kind_code(from_bitboard, to_bitboard, chessboard, promotion_char) as u16;
-        
-        // Determine if he the move has a captured piece.
-        let mut captured_piece: Option<Piece> = None;
-        if to_bitboard & chessboard.get_all_pieces() != 0 {
-            for (i, piece) in chessboard.pieces.iter().enumerate() {
-                if to_bitboard & piece != 0 {
-                    captured_piece = Some(Piece::try_from((i % 6) as i32).unwrap())
+        match self.move_kind_code() & 0b11 {
+            0 => Some(Piece::Knight),
+            1 => Some(Piece::Bishop),
+            2 => Some(Piece::Rook),
+            _ => Some(Piece::Queen),
+        }
+    }
+
+    /// Formats the move in Standard Algebraic Notation (`Nbd7`, `exd6`, `O-O-O`, `e8=Q+`, `Qh4#`).
+    /// 
+    /// The move must be legal on `chessboard`, which is used to disambiguate and to find checks,
+    /// and is left unchanged once the function returns. En passant captures are written like other pawn captures:
+    /// the `e.p.` suffix is accepted by [Move::from_san] but never written, as the PGN standard requires.
+    pub fn to_san(&self, chessboard: &mut Chessboard) -> String {
+        let mut san = match self.move_kind() {
+            MoveKind::KingCastle => "O-O".to_owned(),
+            MoveKind::QueenCastle => "O-O-O".to_owned(),
+            _ => {
+                let from = self.from.trailing_zeros();
+                let to_str = square_name(self.to.trailing_zeros());
+                let mut san = String::new();
+
+                if self.piece_type == Piece::Pawn {
+                    if self.capture_flag() {
+                        san.push((b'a' + (from % 8) as u8) as char);
+                        san.push('x');
+                    }
+                    san.push_str(&to_str);
+                    if let Some(piece) = self.promotion_piece() {
+                        san.push('=');
+                        san.push(char::from(piece).to_ascii_uppercase());
+                    }
                 }
+                else {
+                    san.push(char::from(self.piece_type).to_ascii_uppercase());
+
+                    // Other pieces of the same type which could also go to the destination square
+                    let rivals: Vec<u32> = generate_legal_moves(chessboard)
+                        .iter()
+                        .filter(|mv| mv.piece_type == self.piece_type && mv.to == self.to && mv.from != self.from)
+                        .map(|mv| mv.from.trailing_zeros())
+                        .collect();
+                    if !rivals.is_empty() {
+                        let from_str = square_name(from);
+                        if rivals.iter().all(|rival| rival % 8 != from % 8) {
+                            san.push_str(&from_str[..1]);
+                        }
+                        else if rivals.iter().all(|rival| rival / 8 != from / 8) {
+                            san.push_str(&from_str[1..]);
+                        }
+                        else {
+                            san.push_str(&from_str);
+                        }
+                    }
+
+                    if self.capture_flag() {
+                        san.push('x');
+                    }
+                    san.push_str(&to_str);
+                }
+                san
             }
+        };
+
+        chessboard.make(self);
+        if chessboard.in_check() {
+            san.push(if generate_legal_moves(chessboard).is_empty() { '#' } else { '+' });
         }
+        chessboard.unmake(self);
+
+        san
+    }
+
+    /// Parses a move written in Standard Algebraic Notation, matching it against the legal moves of `chessboard`.
+    /// 
+    /// Check, mate and annotation suffixes (`+`, `#`, `!`, `?`, `e.p.`) are ignored, castling can be written with
+    /// `O` or `0`, and the promotion `=` is optional. `chessboard` is left unchanged once the function returns.
+    pub fn from_san(san: &str, chessboard: &mut Chessboard) -> Result<Move, MoveError> {
+        let malformed = || MoveError::Malformed(san.to_owned());
+        let mut text = san.trim();
+        text = text.strip_suffix("e.p.").unwrap_or(text).trim_end();
+        text = text.trim_end_matches(['+', '#', '!', '?']);
+
+        let legal_moves = generate_legal_moves(chessboard);
+        let candidates: Vec<Move> = match text {
+            "O-O" | "0-0" => legal_moves.into_iter().filter(|mv| mv.move_kind() == MoveKind::KingCastle).collect(),
+            "O-O-O" | "0-0-0" => legal_moves.into_iter().filter(|mv| mv.move_kind() == MoveKind::QueenCastle).collect(),
+            _ => {
+                if !text.is_ascii() {
+                    return Err(malformed());
+                }
+
+                // Promotion suffix, `e8=Q` or `e8Q`
+                let mut promotion = None;
+                if let Some(last) = text.chars().last() && "NBRQ".contains(last) && text.len() > 2 {
+                    promotion = Some(piece_from_san_letter(last).ok_or_else(malformed)?);
+                    text = &text[..text.len() - 1];
+                    text = text.strip_suffix('=').unwrap_or(text);
+                }
+
+                if text.len() < 2 {
+                    return Err(malformed());
+                }
+                let (prefix, to_str) = text.split_at(text.len() - 2);
+                let to = Square::from_str(to_str).map_err(|_| malformed())?.bitboard();
+
+                // Moving piece, pawns have no letter
+                let mut prefix = prefix;
+                let mut piece_type = Piece::Pawn;
+                if let Some(first) = prefix.chars().next() && first.is_ascii_uppercase() {
+                    piece_type = piece_from_san_letter(first).ok_or_else(malformed)?;
+                    prefix = &prefix[1..];
+                }
+                let prefix = prefix.strip_suffix('x').unwrap_or(prefix);
 
-        // Find which piece is on the from square
-        for piece_type_index in 0..6 {
-            if (chessboard.pieces[get_piece_index_raw(chessboard.state.turn_color, piece_type_index)] & from_bitboard) != 0 {
-                return Ok(Move::from(word, Piece::try_from(piece_type_index as i32).unwrap(), captured_piece));
+                // Disambiguation, a file, a rank or both
+                let mut from_file = None;
+                let mut from_rank = None;
+                for c in prefix.chars() {
+                    match c {
+                        'a'..='h' if from_file.is_none() && from_rank.is_none() => from_file = Some((c as u8 - b'a') as u32),
+                        '1'..='8' if from_rank.is_none() => from_rank = Some((c as u8 - b'1') as u32),
+                        _ => return Err(malformed()),
+                    }
+                }
+
+                legal_moves.into_iter().filter(|mv| {
+                    let from = mv.from.trailing_zeros();
+                    mv.piece_type == piece_type
+                        && mv.to == to
+                        && !mv.castle_flag()
+                        && mv.promotion_piece() == promotion
+                        && from_file.is_none_or(|file| from % 8 == file)
+                        && from_rank.is_none_or(|rank| from / 8 == rank)
+                }).collect()
             }
+        };
+
+        match candidates.len() {
+            0 => Err(MoveError::Illegal(san.to_owned())),
+            1 => Ok(candidates.into_iter().next().ok_or_else(malformed)?),
+            _ => Err(MoveError::Ambiguous(san.to_owned())),
         }
-        
-        Err(format!("Could not find piece for move {} on board", uci_move))
+    }
+
+    pub(crate) fn get_ucis() -> Vec<String> {
+        todo!()
+    }
+    
+    /// Decodes a move in UCI long algebraic notation (`e2e4`, `e7e8q`), matching it against the legal moves of `chessboard`.
+    /// 
+    /// The move kind (capture, en passant, castling, promotion) is taken from the generated legal move, so the
+    /// returned move can always be played. `chessboard` is left unchanged once the function returns.
+    pub fn decode_uci(uci_move: &str, chessboard: &mut Chessboard) -> Result<Move, MoveError> {
+        let malformed = || MoveError::Malformed(uci_move.to_owned());
+        let text = uci_move.trim();
+        if !text.is_ascii() || !(4..=5).contains(&text.len()) {
+            return Err(malformed());
+        }
+
+        let from = Square::from_str(&text[0..2]).map_err(|_| malformed())?.bitboard();
+        let to = Square::from_str(&text[2..4]).map_err(|_| malformed())?.bitboard();
+        let promotion = match text[4..].chars().next() {
+            None => None,
+            Some('n') => Some(Piece::Knight),
+            Some('b') => Some(Piece::Bishop),
+            Some('r') => Some(Piece::Rook),
+            Some('q') => Some(Piece::Queen),
+            Some(_) => return Err(malformed()),
+        };
+
+        generate_legal_moves(chessboard)
+            .into_iter()
+            .find(|mv| mv.from == from && mv.to == to && mv.promotion_piece() == promotion)
+            .ok_or_else(|| MoveError::Illegal(uci_move.to_owned()))
+    }
+}
+
+/// Returns the lowercase name of a square index, like `e4`.
+fn square_name(index: u32) -> String {
+    format!("{}{}", (b'a' + (index % 8) as u8) as char, index / 8 + 1)
+}
+
+/// Returns the piece matching an uppercase SAN letter.
+fn piece_from_san_letter(letter: char) -> Option<Piece> {
+    match letter {
+        'N' => Some(Piece::Knight),
+        'B' => Some(Piece::Bishop),
+        'R' => Some(Piece::Rook),
+        'Q' => Some(Piece::Queen),
+        'K' => Some(Piece::King),
+        _ => None,
     }
 }
 
@@ -0,0 +1,61 @@
+#![warn(missing_docs, dead_code)]
+#![deny(unused_imports, unused_mut)]
+#![warn(clippy::missing_docs_in_private_items)]
+#![deny(clippy::unwrap_used, clippy::expect_used)]
+
+use std::fmt;
+
+use crate::engine::models::board::Color;
+
+/// State of a game as returned by [crate::engine::models::board::Chessboard::outcome].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    /// The side to move is checkmated, `winner` is the side that delivered the mate.
+    Checkmate {
+        /// Color of the side that won the game.
+        winner: Color
+    },
+    /// The side to move has no legal move but is not in check.
+    Stalemate,
+    /// Neither side has enough material left to deliver a checkmate.
+    InsufficientMaterial,
+    /// 50 moves have been played by both sides without any capture or pawn move.
+    FiftyMoveRule,
+    /// The same position occurred three times with the same side to move.
+    ThreefoldRepetition,
+    /// The game is not over yet.
+    Ongoing,
+}
+
+impl Outcome {
+    /// Checks if the game is over.
+    pub fn is_over(&self) -> bool {
+        *self != Outcome::Ongoing
+    }
+
+    /// Returns the winner of the game, if any.
+    pub fn winner(&self) -> Option<Color> {
+        match self {
+            Outcome::Checkmate { winner } => Some(*winner),
+            _ => None,
+        }
+    }
+
+    /// Checks if the game ended in a draw.
+    pub fn is_draw(&self) -> bool {
+        self.is_over() && self.winner().is_none()
+    }
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Outcome::Checkmate { winner } => write!(f, "{:?} wins by checkmate", winner),
+            Outcome::Stalemate => write!(f, "Draw by stalemate"),
+            Outcome::InsufficientMaterial => write!(f, "Draw by insufficient material"),
+            Outcome::FiftyMoveRule => write!(f, "Draw by the fifty-move rule"),
+            Outcome::ThreefoldRepetition => write!(f, "Draw by threefold repetition"),
+            Outcome::Ongoing => write!(f, "Game in progress"),
+        }
+    }
+}
@@ -352,6 +352,14 @@ fn king() -> &'static King {
     SLIDING PIECES
 */
 
+/// Allocates a zeroed magic attack table directly on the heap.
+///
+/// `Box::new([[0; 4096]; 64])` builds the 2MB array on the stack first, which overflows the smaller stacks of spawned threads.
+fn boxed_attack_table() -> Box<[[u64; 4096]; 64]> {
+    #[allow(clippy::unwrap_used, reason="The vector always holds exactly 64 tables")]
+    vec![[0u64; 4096]; 64].into_boxed_slice().try_into().unwrap()
+}
+
 /// Represents a bishop piece in chess with precomputed attack patterns using magic bitboards.
 ///
 /// The bishop moves diagonally any number of squares. This struct uses magic bitboards
@@ -442,7 +450,7 @@ fn bishop() -> &'static Bishop {
         let mut bishop = Bishop {
             bishop_blocker_mask: [0; 64],
             bishop_magic_table: Magic::load_magic_table("src/engine/magic/BMagicTable.json").expect("bishop magic table should be found here"),
-            magic_bishop_attacks: Box::new([[0; 4096]; 64]) 
+            magic_bishop_attacks: boxed_attack_table()
         };
 
         // init blocker mask
@@ -571,7 +579,7 @@ fn rook() -> &'static Rook {
         let mut rook = Rook {
             rook_blocker_mask: [0; 64],
             rook_magic_table: Magic::load_magic_table("src/engine/magic/RMagicTable.json").expect("rook magic table should be found here"),
-            magic_rook_attacks: Box::new([[0; 4096]; 64]) 
+            magic_rook_attacks: boxed_attack_table()
         };
 
         // init blocker mask
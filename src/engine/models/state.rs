@@ -14,6 +14,8 @@ use crate::engine::models::board::{Color, Square};
 pub struct State {
     pub(crate) turn_color: Color,
     pub(crate) half_move_clock: u32,
+    /// Number of the full move, starting at 1 and incremented after each black move.
+    pub(crate) full_move_number: u32,
     pub(crate) castling_right: u8,
     pub(crate) en_passant_square: Option<Square>,
     // zobrirst key used to create the tranposition table.
@@ -47,6 +49,7 @@ impl Default for State {
         Self {
             turn_color: Color::White, 
             half_move_clock: 0, 
+            full_move_number: 1,
             castling_right: 0,
             en_passant_square: None,
             zobrist_key: 0 
@@ -9,4 +9,6 @@ pub struct Undo {
     pub castling_right: u8,
     /// Saved half move clock before the move
     pub half_move_clock: u32,
+    /// Saved zobrist key before the move
+    pub zobrist_key: u64,
 }
@@ -1,8 +1,7 @@
-use std::sync::OnceLock;
+use crate::engine::models::{board::{Chessboard, Color, Square}, piece::{Pawn, Piece}, state::State};
 
-use crate::engine::models::{board::Color, piece::Piece, state::State};
-
-struct Zobrist {
+/// Random keys used to incrementally hash a position, see [Chessboard::make].
+pub(crate) struct Zobrist {
     /// Zobrist array
     /// 1 number for each piece at each square                                                   (2 * 6 * 64)
     /// 4 numbers to indicate the castling rights, though usually 16 (2^4) are used for speed    (16)
@@ -29,11 +28,14 @@ struct Zobrist {
     /// 14    | F  | T  | T  | T  | 1110
     /// 15    | T  | T  | T  | T  | 1111
     castling_rights: [u64; 16],
+    /// One key per file of the en passant square.
     en_passant_file: [u64; 8],
+    /// Key toggled every time the side to move changes.
     black_to_move: u64
 }
 
 impl Zobrist {
+    /// Returns the castling part of the hash for a given state.
     pub fn compute_castling_rights_hash(state: State) -> u64 {
         let mut castling_rights_index = 0;
         
@@ -53,39 +55,254 @@ impl Zobrist {
         zobrist().castling_rights[castling_rights_index]
     }
 
+    /// Returns the index of a piece on a square inside `token_square`.
     pub fn get_piece_square_index(color: Color, piece: Piece, square: u8) -> usize {
         (color as usize * 6 + piece as usize) * 64 + square as usize
     }
+
+    /// Returns the key of a piece standing on a square.
+    #[inline(always)]
+    pub(crate) fn piece_square(color: Color, piece: Piece, square: u8) -> u64 {
+        zobrist().token_square[Self::get_piece_square_index(color, piece, square)]
+    }
+
+    /// Returns the key of a castling rights mask, the mask being directly the index of the table.
+    #[inline(always)]
+    pub(crate) fn castling(castling_right: u8) -> u64 {
+        zobrist().castling_rights[castling_right as usize & 15]
+    }
+
+    /// Returns the key of an en passant square, only its file is hashed.
+    #[inline(always)]
+    pub(crate) fn en_passant(square: Square) -> u64 {
+        zobrist().en_passant_file[square as usize % 8]
+    }
+
+    /// Returns the en passant key of `chessboard` if a pawn of `capturer` can take en passant, `0` otherwise.
+    /// 
+    /// An en passant square nobody can capture on does not change the position, so it must not change the key
+    /// either, otherwise a repetition after a double pawn push would be missed.
+    pub(crate) fn en_passant_if_capturable(chessboard: &Chessboard, capturer: Color) -> u64 {
+        match chessboard.state.en_passant_square {
+            Some(square) => {
+                // squares from which a pawn of `capturer` attacks the en passant square
+                let attackers = Pawn::get_attack_mask()[(capturer as usize ^ 1) * 64 + square as usize];
+                if attackers & chessboard.get_piece(capturer, Piece::Pawn) != 0 {
+                    Self::en_passant(square)
+                } else {
+                    0
+                }
+            },
+            None => 0,
+        }
+    }
+
+    /// Returns the key toggled when black is to move.
+    #[inline(always)]
+    pub(crate) fn black_to_move() -> u64 {
+        zobrist().black_to_move
+    }
+
+    /// Computes the full hash of a chessboard from scratch.
+    /// 
+    /// This is used to initialize the key when parsing a FEN, and to validate the incremental key in debug builds.
+    pub(crate) fn compute_hash(chessboard: &Chessboard) -> u64 {
+        let mut key = 0u64;
+
+        for (index, bitboard) in chessboard.pieces.iter().enumerate() {
+            let color = if index < 6 { Color::White } else { Color::Black };
+            let piece = Piece::ALL[index % 6];
+            let mut bits = *bitboard;
+            while bits != 0 {
+                key ^= Self::piece_square(color, piece, bits.trailing_zeros() as u8);
+                bits &= bits - 1;
+            }
+        }
+
+        key ^= Self::compute_castling_rights_hash(chessboard.state);
+        key ^= Self::en_passant_if_capturable(chessboard, chessboard.state.turn_color);
+        if chessboard.state.turn_color == Color::Black {
+            key ^= Self::black_to_move();
+        }
+
+        key
+    }
+
+    /// Computes the pawn key of a chessboard from scratch, hashing its pawns only.
+    ///
+    /// This is used to validate the incremental pawn key in debug builds.
+    pub(crate) fn compute_pawn_hash(chessboard: &Chessboard) -> u64 {
+        let mut key = 0u64;
+        for color in [Color::White, Color::Black] {
+            let mut bits = chessboard.get_piece(color, Piece::Pawn);
+            while bits != 0 {
+                key ^= Self::piece_square(color, Piece::Pawn, bits.trailing_zeros() as u8);
+                bits &= bits - 1;
+            }
+        }
+        key
+    }
 }
 
-/// Lazy static initializer for [Zobrist].
+/// Seed of the zobrist tables, changing it changes every hash (and invalidates anything keyed by them).
+pub(crate) const ZOBRIST_SEED: u64 = 0x2F6B_7A1C_93D4_E5A8;
+
+/// Zobrist tables generated at compile time from [ZOBRIST_SEED], so hashes are identical across runs.
+static ZOBRIST: Zobrist = Zobrist::from_seed(ZOBRIST_SEED);
+
+/// Accessor for the static [Zobrist] tables.
+#[inline(always)]
 fn zobrist() -> &'static Zobrist {
-    static ZOBRIST: OnceLock<Zobrist> = OnceLock::new();
-    ZOBRIST.get_or_init(|| {                
+    &ZOBRIST
+}
+
+/// One step of the SplitMix64 generator, returning the next state and the generated number.
+const fn splitmix64(state: u64) -> (u64, u64) {
+    let state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    let mut z = state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    (state, z ^ (z >> 31))
+}
+
+impl Zobrist {
+    /// Builds every table from a seed using SplitMix64, usable in `const` context.
+    pub(crate) const fn from_seed(seed: u64) -> Self {
+        let mut state = seed;
+        let mut value;
+
         let mut token_square = [0u64; 768];
-        for i in 0..768 {
-            token_square[i] = rand::random();
+        let mut i = 0;
+        while i < 768 {
+            (state, value) = splitmix64(state);
+            token_square[i] = value;
+            i += 1;
         }
 
         let mut castling_rights = [0u64; 16];
-        for i in 0..16 {
-            castling_rights[i] = rand::random();
+        let mut i = 0;
+        while i < 16 {
+            (state, value) = splitmix64(state);
+            castling_rights[i] = value;
+            i += 1;
         }
 
         let mut en_passant_file = [0u64; 8];
-        for i in 0..8 {
-            en_passant_file[i] = rand::random();
+        let mut i = 0;
+        while i < 8 {
+            (state, value) = splitmix64(state);
+            en_passant_file[i] = value;
+            i += 1;
         }
 
-        let black_to_move = rand::random();
+        let (_, black_to_move) = splitmix64(state);
 
-        let zobrist = Zobrist {
+        Zobrist {
             token_square,
             castling_rights,
             en_passant_file,
             black_to_move,
+        }
+    }
+}
+
+/// Polyglot opening book hashing.
+///
+/// Polyglot keys are built from the fixed `Random64` array of the Polyglot format (781 numbers) with its own layout,
+/// so they differ from [Chessboard::zobrist_key]. They are only needed to talk to external tools like `.bin` books.
+///
+/// The array is not shipped with the engine, it is read from a file with [PolyglotKeys::load]. The keys it gives are
+/// checked against the test positions of the Polyglot specification by an ignored test, run it once the file is there
+/// with `cargo test --test zobrist -- --ignored`.
+/// 
+/// # Exemples
+/// ```rust,no_run
+/// use lib::engine::models::{board::Chessboard, zobrist::PolyglotKeys};
+/// 
+/// let keys = PolyglotKeys::load("books/random64.json").unwrap();
+/// assert_eq!(keys.hash(&Chessboard::new()), 0x463b96181691fc9c);
+/// ```
+pub struct PolyglotKeys {
+    /// The `Random64` array: 12 * 64 piece keys, 4 castling keys, 8 en passant keys and the white to move key.
+    random64: Box<[u64; 781]>,
+}
+
+impl PolyglotKeys {
+    /// Offset of the castling keys inside `Random64`.
+    const CASTLE_OFFSET: usize = 768;
+    /// Offset of the en passant keys inside `Random64`.
+    const EN_PASSANT_OFFSET: usize = 772;
+    /// Offset of the turn key inside `Random64`.
+    const TURN_OFFSET: usize = 780;
+
+    /// Builds the keys from a `Random64` array.
+    pub fn new(random64: [u64; 781]) -> Self {
+        Self { random64: Box::new(random64) }
+    }
+
+    /// Loads the `Random64` array from a json file holding an array of 781 integers.
+    pub fn load(path: &str) -> anyhow::Result<Self> {
+        let json_str = std::fs::read_to_string(path)?;
+        let random64: Vec<u64> = serde_json::from_str(&json_str)?;
+        let random64: Box<[u64; 781]> = random64
+            .into_boxed_slice()
+            .try_into()
+            .map_err(|values: Box<[u64]>| anyhow::anyhow!("Random64 must hold 781 numbers, found {}", values.len()))?;
+        Ok(Self { random64 })
+    }
+
+    /// Returns the Polyglot kind of a piece: black pawn = 0, white pawn = 1, black knight = 2, ..., white king = 11.
+    fn kind(color: Color, piece: Piece) -> usize {
+        let piece_order = match piece {
+            Piece::Pawn => 0,
+            Piece::Knight => 1,
+            Piece::Bishop => 2,
+            Piece::Rook => 3,
+            Piece::Queen => 4,
+            Piece::King => 5,
         };
+        piece_order * 2 + (color == Color::White) as usize
+    }
+
+    /// Computes the Polyglot key of a chessboard.
+    /// 
+    /// Following the format, the en passant file is only hashed when a pawn of the side to move could capture on it.
+    pub fn hash(&self, chessboard: &Chessboard) -> u64 {
+        let mut key = 0u64;
+
+        for (index, bitboard) in chessboard.pieces.iter().enumerate() {
+            let color = if index < 6 { Color::White } else { Color::Black };
+            let kind = Self::kind(color, Piece::ALL[index % 6]);
+            let mut bits = *bitboard;
+            while bits != 0 {
+                key ^= self.random64[64 * kind + bits.trailing_zeros() as usize];
+                bits &= bits - 1;
+            }
+        }
+
+        let castling = [
+            chessboard.state.can_white_king_castle(),
+            chessboard.state.can_white_queen_castle(),
+            chessboard.state.can_black_king_castle(),
+            chessboard.state.can_black_queen_castle(),
+        ];
+        for (offset, _) in castling.iter().enumerate().filter(|(_, allowed)| **allowed) {
+            key ^= self.random64[Self::CASTLE_OFFSET + offset];
+        }
 
-        zobrist
-    })
-}
\ No newline at end of file
+        if let Some(en_passant_square) = chessboard.state.en_passant_square {
+            let side = chessboard.state.turn_color;
+            // squares from which a pawn of the side to move attacks the en passant square
+            let attackers = Pawn::get_attack_mask()[(side as usize ^ 1) * 64 + en_passant_square as usize];
+            if attackers & chessboard.get_piece(side, Piece::Pawn) != 0 {
+                key ^= self.random64[Self::EN_PASSANT_OFFSET + en_passant_square as usize % 8];
+            }
+        }
+
+        if chessboard.state.turn_color == Color::White {
+            key ^= self.random64[Self::TURN_OFFSET];
+        }
+
+        key
+    }
+}
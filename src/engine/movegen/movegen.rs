@@ -1,12 +1,41 @@
 use crate::{
     engine::models::{
-        board::{Chessboard, Color},
+        board::{Chessboard, Color, Rank},
         r#move::{Move, MoveKind},
         piece::{Bishop, King, Knight, Pawn, Piece, Queen, Rook},
     },
     utils::bit_operations::pop_1st_bit,
 };
 
+/// Which moves a move generation produces.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MoveGenMode {
+    /// Every move.
+    All,
+    /// Captures, en passant captures and promotions only, used by the quiescence search.
+    Captures,
+}
+
+impl MoveGenMode {
+    /// Returns the squares `piece` of `side` may move to in this mode.
+    #[inline(always)]
+    fn targets(self, chessboard: &Chessboard, side: Color, piece: Piece) -> u64 {
+        match self {
+            MoveGenMode::All => u64::MAX,
+            MoveGenMode::Captures => {
+                let enemies = chessboard.get_color_pieces(side.swap());
+                match piece {
+                    Piece::Pawn => {
+                        let en_passant = chessboard.state.en_passant_square.map_or(0, |square| square.bitboard());
+                        enemies | en_passant | Rank::Rank1.mask() | Rank::Rank8.mask()
+                    },
+                    _ => enemies,
+                }
+            }
+        }
+    }
+}
+
 /// Add pawn moves to the current move generation cycle.
 #[inline(always)]
 pub(crate) fn add_all_possible_moves_pawn(
@@ -151,14 +180,18 @@ pub(crate) fn add_all_possible_moves(
 }
 
 /// Generate all **SPEUDO LEGAL** moves for a given piece and color, updating the `all_pseudo_legal_moves` vector at the same time.
+/// 
+/// Only the moves of `mode` are generated.
 pub(crate) fn get_all_possible_piece_moves(
     chessboard: &Chessboard,
     side: Color,
     piece: Piece,
+    mode: MoveGenMode,
     all_pseudo_legal_moves: &mut Vec<Move>,
 ) {
     let mut pieces = chessboard.get_piece(side, piece);
     let mut _possible_moves = 0u64;
+    let targets = mode.targets(chessboard, side, piece);
 
     match piece {
         Piece::Pawn => {
@@ -166,7 +199,7 @@ pub(crate) fn get_all_possible_piece_moves(
                 let from = 1 << pieces.trailing_zeros();
                 pop_1st_bit(&mut pieces);
 
-                _possible_moves = Pawn::compute_possible_moves(from, chessboard, side);
+                _possible_moves = Pawn::compute_possible_moves(from, chessboard, side) & targets;
                 // println!("PAWN POSSIBLE MOVES:"); 
                 // display_bitstring_as_chessboard(&format!("{:064b}", _possible_moves));
                 add_all_possible_moves_pawn(
@@ -182,7 +215,7 @@ pub(crate) fn get_all_possible_piece_moves(
                 let from = 1 << pieces.trailing_zeros();
                 pop_1st_bit(&mut pieces);
 
-                _possible_moves = Rook::compute_possible_moves(from, chessboard, side);
+                _possible_moves = Rook::compute_possible_moves(from, chessboard, side) & targets;
                 add_all_possible_moves(
                     from,
                     _possible_moves,
@@ -197,7 +230,7 @@ pub(crate) fn get_all_possible_piece_moves(
                 let from = 1 << pieces.trailing_zeros();
                 pop_1st_bit(&mut pieces);
 
-                _possible_moves = Knight::compute_possible_moves(from, chessboard, side);
+                _possible_moves = Knight::compute_possible_moves(from, chessboard, side) & targets;
                 // println!("KNIGHT POSSIBLE MOVES:"); 
                 // display_bitstring_as_chessboard(&format!("{:064b}", _possible_moves));
                 add_all_possible_moves(
@@ -214,7 +247,7 @@ pub(crate) fn get_all_possible_piece_moves(
                 let from = 1 << pieces.trailing_zeros();
                 pop_1st_bit(&mut pieces);
 
-                _possible_moves = Bishop::compute_possible_moves(from, chessboard, side);
+                _possible_moves = Bishop::compute_possible_moves(from, chessboard, side) & targets;
                 // println!("BISHOP POSSIBLE MOVES:"); 
                 // display_bitstring_as_chessboard(&format!("{:064b}", _possible_moves));
                 add_all_possible_moves(
@@ -231,7 +264,7 @@ pub(crate) fn get_all_possible_piece_moves(
                 let from = 1 << pieces.trailing_zeros();
                 pop_1st_bit(&mut pieces);
 
-                _possible_moves = Queen::compute_possible_moves(from, chessboard, side);
+                _possible_moves = Queen::compute_possible_moves(from, chessboard, side) & targets;
                 // println!("QUEEN POSSIBLE MOVES:"); 
                 // display_bitstring_as_chessboard(&format!("{:064b}", _possible_moves));
                 add_all_possible_moves(
@@ -244,9 +277,9 @@ pub(crate) fn get_all_possible_piece_moves(
             }
         }
         Piece::King => {
-            _possible_moves = King::compute_possible_moves(pieces, chessboard, side);
+            _possible_moves = King::compute_possible_moves(pieces, chessboard, side) & targets;
 
-            if chessboard.should_check_castling() {
+            if mode == MoveGenMode::All && chessboard.should_check_castling() {
                 _possible_moves |= King::compute_possible_castling_moves(pieces, chessboard, side);
             }
 
@@ -269,13 +302,25 @@ pub fn generate_moves(chessboard: &Chessboard) -> Vec<Move> {
             chessboard,
             chessboard.state.turn_color,
             Piece::try_from(i).unwrap(),
+            MoveGenMode::All,
             &mut all_pseudo_legal_moves,
         );
     }
     all_pseudo_legal_moves
 }
 
+/// Generate all **LEGAL** moves and return them into a vector.
 pub fn generate_legal_moves(chessboard: &mut Chessboard) -> Vec<Move> {
+    generate_legal_moves_with(chessboard, MoveGenMode::All)
+}
+
+/// Generate the **LEGAL** captures, en passant captures and promotions, see [MoveGenMode::Captures].
+pub fn generate_legal_captures(chessboard: &mut Chessboard) -> Vec<Move> {
+    generate_legal_moves_with(chessboard, MoveGenMode::Captures)
+}
+
+/// Generate the **LEGAL** moves of `mode` and return them into a vector.
+pub fn generate_legal_moves_with(chessboard: &mut Chessboard, mode: MoveGenMode) -> Vec<Move> {
     let mut all_pseudo_legal_moves = Vec::with_capacity(256);
 
     for i in 0..6 {
@@ -283,6 +328,7 @@ pub fn generate_legal_moves(chessboard: &mut Chessboard) -> Vec<Move> {
             chessboard,
             chessboard.state.turn_color,
             Piece::try_from(i).unwrap(),
+            mode,
             &mut all_pseudo_legal_moves,
         );
     }
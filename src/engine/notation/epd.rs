@@ -0,0 +1,274 @@
+#![warn(missing_docs, dead_code)]
+#![deny(unused_imports, unused_mut)]
+#![warn(clippy::missing_docs_in_private_items)]
+#![deny(clippy::unwrap_used, clippy::expect_used)]
+
+use std::fmt;
+
+use crate::engine::models::{board::Chessboard, fen::FenError, r#move::{Move, MoveError}};
+
+/// Error returned when reading an EPD record.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EpdError {
+    /// The record has less than the four position fields.
+    FieldCount {
+        /// Number of position fields found.
+        found: usize
+    },
+    /// The four position fields don't describe a valid position.
+    InvalidFen(FenError),
+    /// A string operand is never closed.
+    Unterminated,
+    /// An opcode doesn't start with a letter or contains something else than letters, digits and `_`.
+    InvalidOpcode {
+        /// The opcode as written.
+        opcode: String
+    },
+    /// An operation is missing an operand it requires.
+    MissingOperand {
+        /// Opcode of the operation.
+        opcode: String
+    },
+    /// An operand can't be read as the value its opcode expects.
+    InvalidOperand {
+        /// Opcode of the operation.
+        opcode: String,
+        /// The operand as written.
+        operand: String
+    },
+    /// A move operand of `bm` or `am` is not a legal move in SAN.
+    InvalidMove {
+        /// Opcode of the operation.
+        opcode: String,
+        /// Why the move can't be read.
+        error: MoveError
+    },
+    /// A record of a multi-line text is invalid.
+    Line {
+        /// Line of the record, starting at 1.
+        line: usize,
+        /// Why the record is invalid.
+        error: Box<EpdError>
+    },
+}
+
+impl fmt::Display for EpdError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EpdError::FieldCount { found } => write!(f, "expected 4 position fields, found {}", found),
+            EpdError::InvalidFen(err) => write!(f, "invalid position: {}", err),
+            EpdError::Unterminated => write!(f, "unterminated string operand"),
+            EpdError::InvalidOpcode { opcode } => write!(f, "invalid opcode '{}'", opcode),
+            EpdError::MissingOperand { opcode } => write!(f, "missing operand for '{}'", opcode),
+            EpdError::InvalidOperand { opcode, operand } => write!(f, "invalid operand '{}' for '{}'", operand, opcode),
+            EpdError::InvalidMove { opcode, error } => write!(f, "{} in '{}'", error, opcode),
+            EpdError::Line { line, error } => write!(f, "{} at line {}", error, line),
+        }
+    }
+}
+
+impl std::error::Error for EpdError {}
+
+/// An EPD operation without a typed field in [Epd], kept as written.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Operation {
+    /// The opcode, i.e. `ce` or `acd`.
+    pub opcode: String,
+    /// The operands, string operands without their quotes.
+    pub operands: Vec<String>,
+}
+
+/// An Extended Position Description record: a position and the operations describing it.
+///
+/// # Exemples
+/// ```rust
+/// use lib::engine::notation::epd::Epd;
+///
+/// let epd = Epd::from_epd(r#"2rr3k/pp3pp1/1nnqbN1p/3pN3/2pP4/2P3Q1/PPB4P/R4RK1 w - - bm Qg6; id "WAC.001";"#).unwrap();
+/// assert_eq!(epd.id.as_deref(), Some("WAC.001"));
+/// assert_eq!(epd.best_moves[0].to_string(), "g3g6");
+/// ```
+#[derive(Clone)]
+pub struct Epd {
+    /// The position, with the clocks given by `hmvc` and `fmvn` when present.
+    pub chessboard: Chessboard,
+    /// `id`, the name of the record in its suite.
+    pub id: Option<String>,
+    /// `bm`, the best moves of the position.
+    pub best_moves: Vec<Move>,
+    /// `am`, the moves to avoid in the position.
+    pub avoid_moves: Vec<Move>,
+    /// `c0` to `c9`, the comments with their number.
+    pub comments: Vec<(u8, String)>,
+    /// `D1` to `Dn`, the expected perft node count of each depth.
+    pub perft: Vec<(u8, u64)>,
+    /// Every other operation, in order.
+    pub operations: Vec<Operation>,
+}
+
+impl Epd {
+    /// Reads a single EPD record.
+    pub fn from_epd(epd: &str) -> Result<Self, EpdError> {
+        let mut rest = epd.trim_start();
+        let mut fields = Vec::with_capacity(4);
+        while fields.len() < 4 && !rest.is_empty() {
+            let end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+            fields.push(&rest[..end]);
+            rest = rest[end..].trim_start();
+        }
+        if fields.len() < 4 {
+            return Err(EpdError::FieldCount { found: fields.len() });
+        }
+
+        let operations = tokenize(rest)?;
+        let mut half_move_clock = "0".to_owned();
+        let mut full_move_number = "1".to_owned();
+        for operation in &operations {
+            match operation.opcode.as_str() {
+                "hmvc" => half_move_clock = single_operand::<u32>(operation)?.to_string(),
+                "fmvn" => full_move_number = single_operand::<u32>(operation)?.to_string(),
+                _ => {}
+            }
+        }
+
+        let fen = format!("{} {} {}", fields.join(" "), half_move_clock, full_move_number);
+        let mut chessboard = Chessboard::from_fen(&fen).map_err(EpdError::InvalidFen)?;
+
+        let mut record = Epd {
+            chessboard: chessboard.clone(),
+            id: None,
+            best_moves: Vec::new(),
+            avoid_moves: Vec::new(),
+            comments: Vec::new(),
+            perft: Vec::new(),
+            operations: Vec::new(),
+        };
+        for operation in operations {
+            let opcode = operation.opcode.as_str();
+            match opcode {
+                "hmvc" | "fmvn" => {}
+                "id" => record.id = Some(single_operand::<String>(&operation)?),
+                "bm" | "am" => {
+                    if operation.operands.is_empty() {
+                        return Err(EpdError::MissingOperand { opcode: operation.opcode });
+                    }
+                    let moves = operation.operands.iter()
+                        .map(|san| Move::from_san(san, &mut chessboard))
+                        .collect::<Result<Vec<_>, _>>()
+                        .map_err(|error| EpdError::InvalidMove { opcode: operation.opcode.clone(), error })?;
+                    if opcode == "bm" {
+                        record.best_moves.extend(moves);
+                    } else {
+                        record.avoid_moves.extend(moves);
+                    }
+                }
+                _ if is_numbered(opcode, 'c') && opcode.len() == 2 => {
+                    let number = opcode[1..].parse().unwrap_or_default();
+                    record.comments.push((number, single_operand::<String>(&operation)?));
+                }
+                _ if is_numbered(opcode, 'D') => {
+                    let depth = opcode[1..].parse::<u8>()
+                        .ok()
+                        .filter(|depth| *depth > 0)
+                        .ok_or(EpdError::InvalidOpcode { opcode: operation.opcode.clone() })?;
+                    record.perft.push((depth, single_operand::<u64>(&operation)?));
+                }
+                _ => record.operations.push(operation),
+            }
+        }
+
+        Ok(record)
+    }
+
+    /// Reads every record of an EPD text, one per line, skipping blank lines and lines starting with `#`.
+    pub fn parse_all(epd: &str) -> Result<Vec<Self>, EpdError> {
+        epd.lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty() && !line.trim_start().starts_with('#'))
+            .map(|(index, line)| Epd::from_epd(line).map_err(|error| EpdError::Line { line: index + 1, error: Box::new(error) }))
+            .collect()
+    }
+
+    /// Returns the comment `c<number>`, if any.
+    pub fn comment(&self, number: u8) -> Option<&str> {
+        self.comments.iter()
+            .find(|(n, _)| *n == number)
+            .map(|(_, comment)| comment.as_str())
+    }
+
+    /// Returns the first operation with the given opcode among the ones without a typed field.
+    pub fn operation(&self, opcode: &str) -> Option<&Operation> {
+        self.operations.iter().find(|operation| operation.opcode == opcode)
+    }
+}
+
+/// Whether `opcode` is `prefix` followed by at least one digit, like `c0` or `D12`.
+fn is_numbered(opcode: &str, prefix: char) -> bool {
+    let mut chars = opcode.chars();
+    chars.next() == Some(prefix) && opcode.len() > 1 && chars.all(|c| c.is_ascii_digit())
+}
+
+/// Reads the single operand of an operation as `T`.
+fn single_operand<T: std::str::FromStr>(operation: &Operation) -> Result<T, EpdError> {
+    let operand = operation.operands.first()
+        .ok_or(EpdError::MissingOperand { opcode: operation.opcode.clone() })?;
+    operand.parse().map_err(|_| EpdError::InvalidOperand {
+        opcode: operation.opcode.clone(),
+        operand: operand.clone(),
+    })
+}
+
+/// Splits the operations following the position fields, each one ended by `;`.
+///
+/// The last `;` may be missing, as in many perft suites.
+fn tokenize(operations: &str) -> Result<Vec<Operation>, EpdError> {
+    let mut result = Vec::new();
+    let mut words: Vec<String> = Vec::new();
+    let mut chars = operations.chars().peekable();
+
+    let mut flush = |words: &mut Vec<String>| -> Result<(), EpdError> {
+        if words.is_empty() {
+            return Ok(());
+        }
+        let opcode = words.remove(0);
+        let valid = opcode.chars().next().is_some_and(|c| c.is_ascii_alphabetic())
+            && opcode.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+        if !valid {
+            return Err(EpdError::InvalidOpcode { opcode });
+        }
+        result.push(Operation { opcode, operands: std::mem::take(words) });
+        Ok(())
+    };
+
+    while let Some(c) = chars.next() {
+        match c {
+            ';' => flush(&mut words)?,
+            '"' => {
+                let mut string = String::new();
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some(c) => string.push(c),
+                        None => return Err(EpdError::Unterminated),
+                    }
+                }
+                words.push(string);
+            }
+            c if c.is_whitespace() => {}
+            c => {
+                let mut word = c.to_string();
+                while let Some(&next) = chars.peek() {
+                    if next.is_whitespace() || next == ';' || next == '"' {
+                        break;
+                    }
+                    word.push(next);
+                    chars.next();
+                }
+                words.push(word);
+            }
+        }
+    }
+    flush(&mut words)?;
+
+    Ok(result)
+}
@@ -0,0 +1,4 @@
+/// Portable Game Notation reader and writer.
+pub mod pgn;
+/// Extended Position Description reader, used by test suites.
+pub mod epd;
@@ -0,0 +1,618 @@
+#![warn(missing_docs, dead_code)]
+#![deny(unused_imports, unused_mut)]
+#![warn(clippy::missing_docs_in_private_items)]
+#![deny(clippy::unwrap_used, clippy::expect_used)]
+
+use std::fmt;
+
+use crate::engine::models::{board::{Chessboard, Color}, fen::FenError, outcome::Outcome, r#move::{Move, MoveError}};
+
+/// Starting position used when a game has no `FEN` tag.
+const STARTING_FEN: &str = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+
+/// The tags every PGN game must have, written first and in this order.
+const SEVEN_TAG_ROSTER: [&str; 7] = ["Event", "Site", "Date", "Round", "White", "Black", "Result"];
+
+/// Maximum length of a movetext line when writing a game.
+const LINE_LENGTH: usize = 80;
+
+/// Result of a game as written in the `Result` tag and at the end of the movetext.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum GameResult {
+    /// `1-0`
+    WhiteWins,
+    /// `0-1`
+    BlackWins,
+    /// `1/2-1/2`
+    Draw,
+    /// `*`, the game is ongoing or its result is unknown.
+    #[default]
+    Unknown,
+}
+
+impl GameResult {
+    /// Parses a result token.
+    pub fn parse(token: &str) -> Option<Self> {
+        match token {
+            "1-0" => Some(GameResult::WhiteWins),
+            "0-1" => Some(GameResult::BlackWins),
+            "1/2-1/2" => Some(GameResult::Draw),
+            "*" => Some(GameResult::Unknown),
+            _ => None,
+        }
+    }
+}
+
+impl From<Outcome> for GameResult {
+    fn from(outcome: Outcome) -> Self {
+        match outcome {
+            Outcome::Checkmate { winner: Color::White } => GameResult::WhiteWins,
+            Outcome::Checkmate { winner: Color::Black } => GameResult::BlackWins,
+            Outcome::Ongoing => GameResult::Unknown,
+            _ => GameResult::Draw,
+        }
+    }
+}
+
+impl fmt::Display for GameResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GameResult::WhiteWins => write!(f, "1-0"),
+            GameResult::BlackWins => write!(f, "0-1"),
+            GameResult::Draw => write!(f, "1/2-1/2"),
+            GameResult::Unknown => write!(f, "*"),
+        }
+    }
+}
+
+/// Error returned when reading a PGN.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PgnError {
+    /// A tag pair is not written as `[Name "value"]`, at the given line.
+    MalformedTag {
+        /// Line of the tag, starting at 1.
+        line: usize
+    },
+    /// A comment or a tag value is never closed.
+    Unterminated {
+        /// Line where the comment or value starts, starting at 1.
+        line: usize
+    },
+    /// Parentheses of the variations don't match, at the given line.
+    UnbalancedVariation {
+        /// Line of the parenthesis, starting at 1.
+        line: usize
+    },
+    /// A variation starts before any move.
+    VariationWithoutMove {
+        /// Line of the parenthesis, starting at 1.
+        line: usize
+    },
+    /// The `FEN` tag does not hold a valid position.
+    InvalidFen(FenError),
+    /// A move can't be played, at the given line.
+    InvalidMove {
+        /// Line of the move, starting at 1.
+        line: usize,
+        /// Why the move can't be played.
+        error: MoveError
+    },
+    /// The text does not contain any game.
+    NoGame,
+}
+
+impl fmt::Display for PgnError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PgnError::MalformedTag { line } => write!(f, "malformed tag pair at line {}", line),
+            PgnError::Unterminated { line } => write!(f, "unterminated comment or string starting at line {}", line),
+            PgnError::UnbalancedVariation { line } => write!(f, "unbalanced variation parenthesis at line {}", line),
+            PgnError::VariationWithoutMove { line } => write!(f, "variation without a preceding move at line {}", line),
+            PgnError::InvalidFen(err) => write!(f, "invalid FEN tag: {}", err),
+            PgnError::InvalidMove { line, error } => write!(f, "{} at line {}", error, line),
+            PgnError::NoGame => write!(f, "no game found"),
+        }
+    }
+}
+
+impl std::error::Error for PgnError {}
+
+/// A move of a game, with the annotations following it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PgnMove {
+    /// The move itself.
+    pub mv: Move,
+    /// The move in Standard Algebraic Notation, as written when exporting.
+    pub san: String,
+    /// Numeric Annotation Glyphs (`$1`, or `!` which is read as `$1`).
+    pub nags: Vec<u8>,
+    /// Comment following the move.
+    pub comment: Option<String>,
+    /// Comment before the move, only read before the first move of a variation, the one before the main line being
+    /// [Game::comment].
+    pub comment_before: Option<String>,
+    /// Numeric Annotation Glyphs before the move, only read before the first move of a line.
+    pub nags_before: Vec<u8>,
+    /// Alternative lines to this move, each starting from the position before it.
+    pub variations: Vec<Vec<PgnMove>>,
+}
+
+/// A game read from or written to PGN.
+///
+/// # Exemples
+/// ```rust
+/// use lib::engine::notation::pgn::{Game, GameResult};
+///
+/// let game = Game::from_pgn("[Event \"?\"]\n\n1. f3 e5 2. g4 Qh4# 0-1").unwrap();
+/// assert_eq!(game.result, GameResult::BlackWins);
+/// assert_eq!(game.replay().unwrap().to_fen(), "rnb1kbnr/pppp1ppp/8/4p3/6Pq/5P2/PPPPP2P/RNBQKBNR w KQkq - 1 3");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Game {
+    /// Tag pairs, in the order they were read or added.
+    pub tags: Vec<(String, String)>,
+    /// Comment written before the first move.
+    pub comment: Option<String>,
+    /// Main line of the game.
+    pub moves: Vec<PgnMove>,
+    /// Result written at the end of the movetext.
+    pub result: GameResult,
+}
+
+impl Game {
+    /// Builds a game from moves played from `chessboard`, the result being its [Outcome] after the last move.
+    ///
+    /// `chessboard` is left unchanged once the function returns.
+    pub fn from_moves(chessboard: &mut Chessboard, moves: &[Move]) -> Self {
+        let mut game = Game::default();
+        let start_fen = chessboard.to_fen();
+        if start_fen != STARTING_FEN {
+            game.set_tag("SetUp", "1");
+            game.set_tag("FEN", &start_fen);
+        }
+
+        for mv in moves {
+            game.moves.push(PgnMove {
+                mv: mv.clone(),
+                san: mv.to_san(chessboard),
+                nags: Vec::new(),
+                comment: None,
+                comment_before: None,
+                nags_before: Vec::new(),
+                variations: Vec::new(),
+            });
+            chessboard.make(mv);
+        }
+
+        game.result = GameResult::from(chessboard.outcome());
+        game.set_tag("Result", &game.result.to_string());
+        for mv in moves.iter().rev() {
+            chessboard.unmake(mv);
+        }
+
+        game
+    }
+
+    /// Reads the first game of a PGN text.
+    pub fn from_pgn(pgn: &str) -> Result<Self, PgnError> {
+        Self::parse_all(pgn)?.into_iter().next().ok_or(PgnError::NoGame)
+    }
+
+    /// Reads every game of a PGN text.
+    pub fn parse_all(pgn: &str) -> Result<Vec<Self>, PgnError> {
+        let tokens = tokenize(pgn)?;
+        let mut games = Vec::new();
+        let mut position = 0;
+
+        while position < tokens.len() {
+            let mut game = Game::default();
+            while let Some((Token::Tag(name, value), _)) = tokens.get(position) {
+                game.tags.push((name.clone(), value.clone()));
+                position += 1;
+            }
+
+            let mut chessboard = game.starting_position()?;
+            let (comment, moves) = parse_line(&tokens, &mut position, &mut chessboard)?;
+            game.comment = comment;
+            game.moves = moves;
+
+            let mut has_result = false;
+            match tokens.get(position) {
+                Some((Token::Result(result), _)) => {
+                    game.result = *result;
+                    has_result = true;
+                    position += 1;
+                },
+                Some((Token::CloseParenthesis, line)) => return Err(PgnError::UnbalancedVariation { line: *line }),
+                // a game without result is ended by the next tag pair or the end of the text
+                _ => game.result = game.tag("Result").and_then(GameResult::parse).unwrap_or_default(),
+            }
+            // comments and NAGs after the result annotate nothing, they don't start another game
+            while let Some((Token::Comment(_) | Token::Nag(_), _)) = tokens.get(position) {
+                position += 1;
+            }
+
+            if !game.tags.is_empty() || !game.moves.is_empty() || has_result {
+                games.push(game);
+            }
+        }
+
+        Ok(games)
+    }
+
+    /// Returns the value of a tag, if present.
+    pub fn tag(&self, name: &str) -> Option<&str> {
+        self.tags.iter().find(|(tag, _)| tag == name).map(|(_, value)| value.as_str())
+    }
+
+    /// Sets the value of a tag, replacing the previous one if any.
+    pub fn set_tag(&mut self, name: &str, value: &str) {
+        match self.tags.iter_mut().find(|(tag, _)| tag == name) {
+            Some((_, old_value)) => *old_value = value.to_owned(),
+            None => self.tags.push((name.to_owned(), value.to_owned())),
+        }
+    }
+
+    /// Returns the position the game starts from, given by the `FEN` tag or the standard starting position.
+    pub fn starting_position(&self) -> Result<Chessboard, PgnError> {
+        Chessboard::from_fen(self.tag("FEN").unwrap_or(STARTING_FEN)).map_err(PgnError::InvalidFen)
+    }
+
+    /// Plays the main line on the starting position, returning the final chessboard.
+    ///
+    /// Every move is played with [Chessboard::make], so they can be taken back with [Chessboard::unmake].
+    pub fn replay(&self) -> Result<Chessboard, PgnError> {
+        let mut chessboard = self.starting_position()?;
+        for pgn_move in &self.moves {
+            chessboard.make(&pgn_move.mv);
+        }
+        Ok(chessboard)
+    }
+
+    /// Returns the FEN of every position of the main line, starting with the starting position.
+    pub fn positions(&self) -> Result<Vec<String>, PgnError> {
+        let mut chessboard = self.starting_position()?;
+        let mut positions = vec![chessboard.to_fen()];
+        for pgn_move in &self.moves {
+            chessboard.make(&pgn_move.mv);
+            positions.push(chessboard.to_fen());
+        }
+        Ok(positions)
+    }
+
+    /// Writes the game as PGN, the seven tag roster first, with the movetext wrapped at 80 columns.
+    pub fn to_pgn(&self) -> String {
+        let mut pgn = String::new();
+
+        for name in SEVEN_TAG_ROSTER {
+            let value = match name {
+                "Result" => self.result.to_string(),
+                "Date" => self.tag(name).unwrap_or("????.??.??").to_owned(),
+                _ => self.tag(name).unwrap_or("?").to_owned(),
+            };
+            pgn.push_str(&format!("[{} \"{}\"]\n", name, escape(&value)));
+        }
+        for (name, value) in self.tags.iter().filter(|(name, _)| !SEVEN_TAG_ROSTER.contains(&name.as_str())) {
+            pgn.push_str(&format!("[{} \"{}\"]\n", name, escape(value)));
+        }
+        pgn.push('\n');
+
+        let mut words = Vec::new();
+        if let Some(comment) = &self.comment {
+            push_comment(comment, &mut words);
+        }
+        let (full_move_number, turn_color) = match self.starting_position() {
+            Ok(chessboard) => (chessboard.state.full_move_number, chessboard.get_current_turn()),
+            Err(_) => (1, Color::White),
+        };
+        let first_ply = (full_move_number.max(1) as usize - 1) * 2 + (turn_color == Color::Black) as usize;
+        write_line(&self.moves, first_ply, &mut words);
+        words.push(self.result.to_string());
+
+        let mut line = String::new();
+        for word in words {
+            if !line.is_empty() && line.len() + 1 + word.len() > LINE_LENGTH {
+                pgn.push_str(&line);
+                pgn.push('\n');
+                line.clear();
+            }
+            if !line.is_empty() {
+                line.push(' ');
+            }
+            line.push_str(&word);
+        }
+        pgn.push_str(&line);
+        pgn.push('\n');
+
+        pgn
+    }
+}
+
+/// Escapes the quotes and backslashes of a tag value.
+fn escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+/// Appends a `{comment}` to `words` one word at a time, so long comments can be wrapped too.
+fn push_comment(comment: &str, words: &mut Vec<String>) {
+    let mut comment_words: Vec<String> = comment.split_whitespace().map(str::to_owned).collect();
+    if comment_words.is_empty() {
+        comment_words.push(String::new());
+    }
+    if let Some(first) = comment_words.first_mut() {
+        first.insert(0, '{');
+    }
+    if let Some(last) = comment_words.last_mut() {
+        last.push('}');
+    }
+    words.extend(comment_words);
+}
+
+/// Appends the movetext of a line to `words`, `ply` being the ply of its first move counted from move 1 with white.
+fn write_line(moves: &[PgnMove], mut ply: usize, words: &mut Vec<String>) {
+    let mut needs_number = true;
+    for pgn_move in moves {
+        if let Some(comment) = &pgn_move.comment_before {
+            push_comment(comment, words);
+        }
+        for nag in &pgn_move.nags_before {
+            words.push(format!("${}", nag));
+        }
+        if ply.is_multiple_of(2) {
+            words.push(format!("{}.", ply / 2 + 1));
+        } else if needs_number {
+            words.push(format!("{}...", ply / 2 + 1));
+        }
+        words.push(pgn_move.san.clone());
+        needs_number = false;
+
+        for nag in &pgn_move.nags {
+            words.push(format!("${}", nag));
+        }
+        if let Some(comment) = &pgn_move.comment {
+            push_comment(comment, words);
+            needs_number = true;
+        }
+        for variation in &pgn_move.variations {
+            let mut variation_words = Vec::new();
+            write_line(variation, ply, &mut variation_words);
+            if let Some(first) = variation_words.first_mut() {
+                first.insert(0, '(');
+            }
+            if let Some(last) = variation_words.last_mut() {
+                last.push(')');
+            }
+            words.extend(variation_words);
+            needs_number = true;
+        }
+        ply += 1;
+    }
+}
+
+/// Lexical element of a PGN text.
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    /// `[Name "value"]`
+    Tag(String, String),
+    /// A move in SAN, move numbers are dropped.
+    San(String),
+    /// `{comment}` or `; comment`
+    Comment(String),
+    /// `$n`, or a move suffix like `!?`
+    Nag(u8),
+    /// `(`
+    OpenParenthesis,
+    /// `)`
+    CloseParenthesis,
+    /// Game termination marker.
+    Result(GameResult),
+}
+
+/// Splits a PGN text into tokens, each paired with its line number.
+fn tokenize(pgn: &str) -> Result<Vec<(Token, usize)>, PgnError> {
+    let chars: Vec<char> = pgn.chars().collect();
+    let mut tokens = Vec::new();
+    let mut line = 1;
+    let mut i = 0;
+
+    // Reads characters until `end`, returning the text in between and moving after `end`.
+    let read_until = |i: &mut usize, line: &mut usize, end: char| -> Option<String> {
+        let mut text = String::new();
+        while *i < chars.len() {
+            let c = chars[*i];
+            *i += 1;
+            if c == end {
+                return Some(text);
+            }
+            if c == '\n' {
+                *line += 1;
+            }
+            text.push(c);
+        }
+        None
+    };
+
+    // escape mechanism on the first line, the other lines being handled after their line break
+    if chars.first() == Some(&'%') {
+        read_until(&mut i, &mut line, '\n');
+        line += 1;
+    }
+
+    while i < chars.len() {
+        let c = chars[i];
+        match c {
+            '\n' => {
+                line += 1;
+                i += 1;
+                // escape mechanism, the whole line is ignored
+                if chars.get(i) == Some(&'%') {
+                    read_until(&mut i, &mut line, '\n');
+                    line += 1;
+                }
+            },
+            c if c.is_whitespace() => i += 1,
+            '[' => {
+                let start_line = line;
+                i += 1;
+                let content = read_until(&mut i, &mut line, ']').ok_or(PgnError::Unterminated { line: start_line })?;
+                tokens.push((parse_tag(&content).ok_or(PgnError::MalformedTag { line: start_line })?, start_line));
+            },
+            '{' => {
+                let start_line = line;
+                i += 1;
+                let comment = read_until(&mut i, &mut line, '}').ok_or(PgnError::Unterminated { line: start_line })?;
+                // line breaks inside a comment are only layout, the writer wraps them again
+                let comment = comment.split_whitespace().collect::<Vec<_>>().join(" ");
+                tokens.push((Token::Comment(comment), start_line));
+            },
+            ';' => {
+                i += 1;
+                let comment = read_until(&mut i, &mut line, '\n').unwrap_or_default();
+                tokens.push((Token::Comment(comment.trim().to_owned()), line));
+                line += 1;
+            },
+            '(' => {
+                tokens.push((Token::OpenParenthesis, line));
+                i += 1;
+            },
+            ')' => {
+                tokens.push((Token::CloseParenthesis, line));
+                i += 1;
+            },
+            _ => {
+                let start = i;
+                while i < chars.len() && !chars[i].is_whitespace() && !"[]{}();".contains(chars[i]) {
+                    i += 1;
+                }
+                let symbol: String = chars[start..i].iter().collect();
+                tokens.extend(parse_symbol(&symbol).into_iter().map(|token| (token, line)));
+            },
+        }
+    }
+
+    Ok(tokens)
+}
+
+/// Parses the inside of a tag pair, `Name "value"`.
+fn parse_tag(content: &str) -> Option<Token> {
+    let content = content.trim();
+    let (name, value) = content.split_once(char::is_whitespace)?;
+    let value = value.trim().strip_prefix('"')?.strip_suffix('"')?;
+
+    let mut unescaped = String::new();
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => unescaped.push(chars.next()?),
+            _ => unescaped.push(c),
+        }
+    }
+    Some(Token::Tag(name.to_owned(), unescaped))
+}
+
+/// Parses a symbol of the movetext: a move number, a move with its suffix annotations, a NAG or a result.
+fn parse_symbol(symbol: &str) -> Vec<Token> {
+    if let Some(result) = GameResult::parse(symbol) {
+        return vec![Token::Result(result)];
+    }
+    if let Some(nag) = symbol.strip_prefix('$') {
+        return nag.parse().map(|nag| vec![Token::Nag(nag)]).unwrap_or_default();
+    }
+
+    // move numbers like `12.` or `12...`, possibly glued to the move as in `12.e4`, castling with zeros excepted, and
+    // the `e.p.` suffix of en passant captures, possibly glued to the move as in `exd6e.p.`
+    let mut san = symbol.strip_suffix("e.p.").unwrap_or(symbol);
+    if !san.starts_with("0-0") {
+        san = san.trim_start_matches(|c: char| c.is_ascii_digit()).trim_start_matches('.');
+    }
+    if san.is_empty() {
+        return Vec::new();
+    }
+
+    let suffix_start = san.find(['!', '?']).unwrap_or(san.len());
+    let (san, suffix) = san.split_at(suffix_start);
+    let mut tokens = Vec::new();
+    if !san.is_empty() {
+        tokens.push(Token::San(san.to_owned()));
+    }
+    let nag = match suffix {
+        "!" => Some(1),
+        "?" => Some(2),
+        "!!" => Some(3),
+        "??" => Some(4),
+        "!?" => Some(5),
+        "?!" => Some(6),
+        _ => None,
+    };
+    tokens.extend(nag.map(Token::Nag));
+    tokens
+}
+
+/// Parses a line of moves from `position` until a result, a closing parenthesis or the next game,
+/// returning the comment before its first move and its moves, the NAGs before its first move being kept in it.
+///
+/// The moves are played on `chessboard` to resolve the SAN, and taken back before returning.
+fn parse_line(tokens: &[(Token, usize)], position: &mut usize, chessboard: &mut Chessboard) -> Result<(Option<String>, Vec<PgnMove>), PgnError> {
+    let mut leading_comment: Option<String> = None;
+    let mut leading_nags: Vec<u8> = Vec::new();
+    let mut moves: Vec<PgnMove> = Vec::new();
+
+    while let Some((token, line)) = tokens.get(*position) {
+        match token {
+            Token::San(san) => {
+                let mv = Move::from_san(san, chessboard).map_err(|error| PgnError::InvalidMove { line: *line, error })?;
+                let san = mv.to_san(chessboard);
+                chessboard.make(&mv);
+                moves.push(PgnMove {
+                    mv,
+                    san,
+                    nags: Vec::new(),
+                    comment: None,
+                    comment_before: None,
+                    nags_before: std::mem::take(&mut leading_nags),
+                    variations: Vec::new(),
+                });
+            },
+            Token::Comment(comment) => {
+                let target = match moves.last_mut() {
+                    Some(last) => &mut last.comment,
+                    None => &mut leading_comment,
+                };
+                *target = Some(match target.take() {
+                    Some(previous) => format!("{} {}", previous, comment),
+                    None => comment.clone(),
+                });
+            },
+            Token::Nag(nag) => {
+                match moves.last_mut() {
+                    Some(last) => last.nags.push(*nag),
+                    None => leading_nags.push(*nag),
+                }
+            },
+            Token::OpenParenthesis => {
+                let line = *line;
+                let last = moves.last_mut().ok_or(PgnError::VariationWithoutMove { line })?;
+                // a variation replaces the last move, so it starts from the position before it
+                chessboard.unmake(&last.mv);
+                *position += 1;
+                let (comment, mut variation) = parse_line(tokens, position, chessboard)?;
+                if let Some(first) = variation.first_mut() {
+                    first.comment_before = comment;
+                }
+                chessboard.make(&last.mv);
+                match tokens.get(*position) {
+                    Some((Token::CloseParenthesis, _)) => {},
+                    _ => return Err(PgnError::UnbalancedVariation { line }),
+                }
+                last.variations.push(variation);
+            },
+            Token::CloseParenthesis | Token::Result(_) | Token::Tag(..) => break,
+        }
+        *position += 1;
+    }
+
+    for pgn_move in moves.iter().rev() {
+        chessboard.unmake(&pgn_move.mv);
+    }
+    Ok((leading_comment, moves))
+}
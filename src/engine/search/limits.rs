@@ -0,0 +1,67 @@
+use std::time::Duration;
+
+/// Limits of a search, as given by the arguments of the UCI `go` command.
+///
+/// Arguments that are missing or can't be read are left to `None`, a `go` without any limit searches
+/// up to the default depth of the [Search](crate::engine::search::Search).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SearchLimits {
+    /// `wtime`, time left on white's clock.
+    pub wtime: Option<Duration>,
+    /// `btime`, time left on black's clock.
+    pub btime: Option<Duration>,
+    /// `winc`, white's increment per move.
+    pub winc: Option<Duration>,
+    /// `binc`, black's increment per move.
+    pub binc: Option<Duration>,
+    /// `movestogo`, moves left until the next time control.
+    pub movestogo: Option<u32>,
+    /// `movetime`, exact time to search.
+    pub movetime: Option<Duration>,
+    /// `depth`, maximum depth to search.
+    pub depth: Option<i32>,
+    /// `nodes`, maximum number of nodes to search.
+    pub nodes: Option<u64>,
+    /// `infinite`, search until `stop`.
+    pub infinite: bool,
+    /// `ponder`, search the expected reply of the opponent until `ponderhit` or `stop`.
+    pub ponder: bool,
+}
+
+impl SearchLimits {
+    /// Parses the arguments of a `go` command, the command itself being optional.
+    pub fn parse(go: &str) -> Self {
+        let mut limits = SearchLimits::default();
+        let mut tokens = go.split_whitespace().peekable();
+
+        while let Some(token) = tokens.next() {
+            match token {
+                "wtime" => limits.wtime = tokens.next().and_then(parse_millis),
+                "btime" => limits.btime = tokens.next().and_then(parse_millis),
+                "winc" => limits.winc = tokens.next().and_then(parse_millis),
+                "binc" => limits.binc = tokens.next().and_then(parse_millis),
+                "movetime" => limits.movetime = tokens.next().and_then(parse_millis),
+                "movestogo" => limits.movestogo = tokens.next().and_then(|value| value.parse().ok()),
+                "depth" => limits.depth = tokens.next().and_then(|value| value.parse().ok()),
+                "nodes" => limits.nodes = tokens.next().and_then(|value| value.parse().ok()),
+                "infinite" => limits.infinite = true,
+                "ponder" => limits.ponder = true,
+                _ => {}
+            }
+        }
+
+        limits
+    }
+
+    /// Checks if the search is bounded by a clock or a move time.
+    pub fn is_timed(&self) -> bool {
+        self.movetime.is_some() || self.wtime.is_some() || self.btime.is_some()
+    }
+}
+
+/// Parses a number of milliseconds, negative values sent by some GUIs once the time is up being read as zero.
+fn parse_millis(value: &str) -> Option<Duration> {
+    value.parse::<i64>()
+        .ok()
+        .map(|millis| Duration::from_millis(millis.max(0) as u64))
+}
@@ -1,3 +1,17 @@
 pub mod evaluation;
 pub mod search;
-pub use search::*;
\ No newline at end of file
+/// Limits of a search, parsed from the UCI `go` command.
+pub mod limits;
+/// Time management turning the clock state into search deadlines.
+pub mod time;
+/// Triangular table collecting the principal variation.
+pub mod pv;
+/// Move ordering heuristics: hash move, MVV-LVA, killer moves and history.
+pub mod ordering;
+/// Fixed-size transposition table shared between search threads.
+pub mod tt;
+/// Switches and tables of the pruning and reduction techniques.
+pub mod selectivity;
+/// Pawn structure evaluation, cached in a pawn hash table.
+pub mod pawns;
+pub use search::*;
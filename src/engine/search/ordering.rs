@@ -0,0 +1,137 @@
+use crate::engine::{models::{board::Color, piece::Piece, r#move::{Move, MoveKind}}, search::MAX_PLY};
+
+/// Score of the hash move, searched before anything else.
+const TT_MOVE_SCORE: i32 = 1_000_000;
+/// Base score of captures and promotions, above every quiet move.
+const CAPTURE_SCORE: i32 = 100_000;
+/// Score of the first killer move of a ply, the second one scoring a bit less.
+const KILLER_SCORE: i32 = 90_000;
+/// Bound of the history scores, kept below the killer moves.
+pub const MAX_HISTORY: i32 = 16_384;
+
+/// Rank of a piece in the MVV-LVA ordering, from the least to the most valuable.
+#[inline]
+fn mvv_lva_rank(piece: Piece) -> i32 {
+    match piece {
+        Piece::Pawn => 1,
+        Piece::Knight => 2,
+        Piece::Bishop => 3,
+        Piece::Rook => 4,
+        Piece::Queen => 5,
+        Piece::King => 6,
+    }
+}
+
+/// Checks if `mv` is a quiet move, i.e. neither a capture nor a promotion.
+#[inline]
+pub fn is_quiet(mv: &Move) -> bool {
+    !mv.capture_flag() && !mv.promotion_flag()
+}
+
+/// Heuristics ordering the moves of a node, filled in by the search as moves cause beta cutoffs.
+///
+/// - killer moves: the last two quiet moves that caused a cutoff at a ply, likely to refute its siblings too.
+/// - history: how often a quiet move of a piece to a square caused a cutoff, by side to move.
+#[derive(Clone)]
+pub struct MoveOrdering {
+    /// Two killer moves per ply, the most recent first.
+    killers: Vec<[Option<Move>; 2]>,
+    /// History score by color, piece and destination square.
+    history: [[[i32; 64]; 6]; 2],
+}
+
+impl Default for MoveOrdering {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MoveOrdering {
+    /// Creates empty heuristics.
+    pub fn new() -> Self {
+        Self {
+            killers: vec![[None, None]; MAX_PLY + 1],
+            history: [[[0; 64]; 6]; 2],
+        }
+    }
+
+    /// Forgets the killer moves and history of the previous search.
+    pub fn clear(&mut self) {
+        self.killers.iter_mut().for_each(|killers| *killers = [None, None]);
+        self.history = [[[0; 64]; 6]; 2];
+    }
+
+    /// Records that the quiet move `mv` of `color` caused a beta cutoff at `ply` and `depth`, the quiet moves
+    /// searched before it without doing so being `tried`.
+    pub fn update(&mut self, mv: &Move, tried: &[Move], color: Color, ply: usize, depth: i32) {
+        let killers = &mut self.killers[ply];
+        if killers[0].as_ref() != Some(mv) {
+            killers[1] = killers[0].replace(mv.clone());
+        }
+
+        let bonus = (depth * depth).min(MAX_HISTORY);
+        self.update_history(mv, color, bonus);
+        for quiet in tried {
+            self.update_history(quiet, color, -bonus);
+        }
+    }
+
+    /// Adds `bonus` to the history of `mv`, scores getting harder to move as they near [MAX_HISTORY].
+    fn update_history(&mut self, mv: &Move, color: Color, bonus: i32) {
+        let entry = &mut self.history[color as usize][mv.piece_type as usize][mv.to.trailing_zeros() as usize];
+        *entry += bonus - *entry * bonus.abs() / MAX_HISTORY;
+    }
+
+    /// Returns the history score of the quiet move `mv` of `color`, within [-MAX_HISTORY, MAX_HISTORY].
+    pub fn history(&self, mv: &Move, color: Color) -> i32 {
+        self.history[color as usize][mv.piece_type as usize][mv.to.trailing_zeros() as usize]
+    }
+
+    /// Scores `mv` for `color` at `ply`, the higher the sooner it is searched.
+    fn score(&self, mv: &Move, tt_move: Option<&Move>, color: Color, ply: usize) -> i32 {
+        if tt_move == Some(mv) {
+            return TT_MOVE_SCORE;
+        }
+
+        if !is_quiet(mv) {
+            // an en passant capture takes a pawn that isn't on the destination square
+            let victim = mv.captured_piece.or((mv.move_kind() == MoveKind::EpCapture).then_some(Piece::Pawn));
+            let promotion = mv.promotion_piece().map_or(0, mvv_lva_rank);
+            return CAPTURE_SCORE + victim.map_or(0, mvv_lva_rank) * 10 + promotion * 10 - mvv_lva_rank(mv.piece_type);
+        }
+
+        match &self.killers[ply] {
+            [Some(killer), _] if killer == mv => KILLER_SCORE,
+            [_, Some(killer)] if killer == mv => KILLER_SCORE - 1,
+            _ => self.history(mv, color),
+        }
+    }
+
+    /// Returns a [MovePicker] over `moves` for `color` at `ply`, `tt_move` being searched first.
+    pub fn picker(&self, moves: Vec<Move>, tt_move: Option<&Move>, color: Color, ply: usize) -> MovePicker {
+        let scores = moves.iter().map(|mv| self.score(mv, tt_move, color, ply)).collect();
+        MovePicker { moves, scores }
+    }
+}
+
+/// Yields the moves of a node from the highest score to the lowest.
+///
+/// Moves are picked one at a time rather than sorted upfront, most nodes being cut off after a few of them.
+pub struct MovePicker {
+    moves: Vec<Move>,
+    scores: Vec<i32>,
+}
+
+impl Iterator for MovePicker {
+    type Item = Move;
+
+    fn next(&mut self) -> Option<Move> {
+        let (best, _) = self.scores.iter().enumerate().max_by_key(|&(_, score)| *score)?;
+        self.scores.swap_remove(best);
+        Some(self.moves.swap_remove(best))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.moves.len(), Some(self.moves.len()))
+    }
+}
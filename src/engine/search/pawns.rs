@@ -0,0 +1,239 @@
+use crate::engine::models::{board::{Chessboard, Color}, piece::{Pawn, Piece}};
+
+/// Number of entries of a [PawnTable], a power of two.
+const PAWN_TABLE_SIZE: usize = 1 << 14;
+
+/// Mask of the `a` file, shifted to get the other files.
+const FILE_A: u64 = 0x0101_0101_0101_0101;
+
+// Pawn structure terms, as (midgame, endgame) pairs in centipawns. Terms by rank are indexed by the rank of the pawn
+// counted from its own side, `0` being its first rank.
+
+/// Bonus of a passed pawn by rank, halved while an enemy piece blocks it.
+const PASSED: [(i32, i32); 8] = [(0, 0), (2, 8), (5, 12), (10, 20), (25, 40), (45, 70), (70, 110), (0, 0)];
+/// Bonus of a pawn defended by another pawn, by rank.
+const SUPPORTED: [(i32, i32); 8] = [(0, 0), (0, 0), (6, 4), (8, 6), (14, 10), (22, 18), (35, 28), (0, 0)];
+/// Bonus of a pawn standing next to another pawn, by rank.
+const PHALANX: [(i32, i32); 8] = [(0, 0), (3, 2), (5, 3), (9, 6), (16, 12), (28, 20), (45, 35), (0, 0)];
+/// Penalty of a pawn with no pawn of its side on the adjacent files.
+const ISOLATED: (i32, i32) = (-8, -14);
+/// Penalty of a pawn with another pawn of its side in front of it.
+const DOUBLED: (i32, i32) = (-10, -22);
+/// Penalty of a pawn left behind by the pawns of the adjacent files, which can't advance safely.
+const BACKWARD: (i32, i32) = (-8, -10);
+
+/// Midgame bonus of a pawn right in front of its king, on the king file or an adjacent one.
+const SHIELD_CLOSE: i32 = 12;
+/// Midgame bonus of a pawn two ranks in front of its king, on the king file or an adjacent one.
+const SHIELD_FAR: i32 = 6;
+/// Midgame penalty of a file around the king without any pawn of its side in front of it.
+const SHIELD_MISSING: i32 = -12;
+
+/// Returns the mask of the files next to the file of `square`.
+#[inline]
+fn adjacent_files(square: u32) -> u64 {
+    let file = FILE_A << (square % 8);
+    ((file << 1) & !FILE_A) | ((file >> 1) & !(FILE_A << 7))
+}
+
+/// Returns the mask of the rank of `square`.
+#[inline]
+fn rank_mask(square: u32) -> u64 {
+    0xFF << (square / 8 * 8)
+}
+
+/// Returns the mask of the ranks in front of `square`, from the point of view of `color`.
+#[inline]
+fn ranks_ahead(color: Color, square: u32) -> u64 {
+    let rank = square / 8;
+    match color {
+        Color::White if rank < 7 => u64::MAX << ((rank + 1) * 8),
+        Color::Black if rank > 0 => u64::MAX >> ((8 - rank) * 8),
+        _ => 0,
+    }
+}
+
+/// Returns the rank of `square` counted from the side of `color`, `0` being its first rank.
+#[inline]
+fn relative_rank(color: Color, square: u32) -> usize {
+    match color {
+        Color::White => square as usize / 8,
+        Color::Black => 7 - square as usize / 8,
+    }
+}
+
+/// Returns the square in front of `square`, from the point of view of `color`.
+#[inline]
+fn square_ahead(color: Color, square: u32) -> u32 {
+    match color {
+        Color::White => square + 8,
+        Color::Black => square - 8,
+    }
+}
+
+/// Returns the squares attacked by a pawn of `color` standing on `square`.
+#[inline]
+fn pawn_attacks(color: Color, square: u32) -> u64 {
+    Pawn::get_attack_mask()[color as usize * 64 + square as usize]
+}
+
+/// Pawn structure of a position, from white's point of view, as cached by the [PawnTable].
+///
+/// The terms that only depend on the pawns are scored once and for all. Passed pawns are only found, their bonus
+/// depending on the pieces blocking them, see [PawnTable::evaluate].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct PawnEntry {
+    /// Pawn key of the position, see [Chessboard::pawn_key].
+    pub key: u64,
+    /// Midgame score of the pawn structure, in centipawns.
+    pub mg: i32,
+    /// Endgame score of the pawn structure, in centipawns.
+    pub eg: i32,
+    /// Passed pawns of each color, by [Color].
+    pub passed: [u64; 2],
+}
+
+impl PawnEntry {
+    /// Scores the pawn structure of `chessboard` from scratch.
+    pub fn compute(chessboard: &Chessboard) -> Self {
+        let mut entry = Self { key: chessboard.pawn_key(), ..Default::default() };
+        for color in [Color::White, Color::Black] {
+            let sign = if color == Color::White { 1 } else { -1 };
+            let own = chessboard.get_piece(color, Piece::Pawn);
+            let enemy = chessboard.get_piece(color.swap(), Piece::Pawn);
+
+            let mut bits = own;
+            while bits != 0 {
+                let square = bits.trailing_zeros();
+                bits &= bits - 1;
+
+                let rank = relative_rank(color, square);
+                let file = FILE_A << (square % 8);
+                let adjacent = adjacent_files(square);
+                let ahead = ranks_ahead(color, square);
+                let doubled = own & file & ahead != 0;
+                if doubled {
+                    entry.add(sign, DOUBLED);
+                }
+                // the rearmost pawn of a doubled pair is not passed, the one in front of it is
+                if !doubled && enemy & (file | adjacent) & ahead == 0 {
+                    entry.passed[color as usize] |= 1 << square;
+                }
+
+                if own & adjacent == 0 {
+                    entry.add(sign, ISOLATED);
+                } else if own & adjacent & !ahead == 0 && pawn_attacks(color, square_ahead(color, square)) & enemy != 0 {
+                    // no pawn of the adjacent files can defend it as it advances, and an enemy pawn takes it if it does
+                    entry.add(sign, BACKWARD);
+                }
+
+                if pawn_attacks(color.swap(), square) & own != 0 {
+                    entry.add(sign, SUPPORTED[rank]);
+                }
+                if own & adjacent & rank_mask(square) != 0 {
+                    entry.add(sign, PHALANX[rank]);
+                }
+            }
+        }
+        entry
+    }
+
+    /// Adds a (midgame, endgame) `term` to the score, `sign` being `-1` for the terms of black.
+    #[inline]
+    fn add(&mut self, sign: i32, (mg, eg): (i32, i32)) {
+        self.mg += sign * mg;
+        self.eg += sign * eg;
+    }
+}
+
+/// Hash table of the pawn structures scored by a search thread, keyed by [Chessboard::pawn_key].
+///
+/// Pawns move rarely compared to the other pieces, most positions of a search share their pawn structure with many
+/// others, so it is scored once for all of them. Entries are replaced as soon as another structure maps to them.
+#[derive(Clone)]
+pub struct PawnTable {
+    entries: Vec<PawnEntry>,
+}
+
+impl Default for PawnTable {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PawnTable {
+    /// Creates an empty table.
+    pub fn new() -> Self {
+        Self { entries: vec![PawnEntry::default(); PAWN_TABLE_SIZE] }
+    }
+
+    /// Returns the pawn structure of `chessboard`, scoring it first unless it is already in the table.
+    pub fn probe(&mut self, chessboard: &Chessboard) -> &PawnEntry {
+        let key = chessboard.pawn_key();
+        let index = key as usize & (PAWN_TABLE_SIZE - 1);
+        // positions without pawns have a key of `0`, which an empty entry already scores right
+        if self.entries[index].key != key {
+            self.entries[index] = PawnEntry::compute(chessboard);
+        }
+        &self.entries[index]
+    }
+
+    /// Evaluates the pawns of `chessboard` in centipawns, from white's point of view, as a (midgame, endgame) pair.
+    ///
+    /// On top of the cached pawn structure, passed pawns are scaled down while blocked and kings are rewarded for the
+    /// pawns sheltering them, both depending on other pieces than pawns.
+    pub fn evaluate(&mut self, chessboard: &Chessboard) -> (i32, i32) {
+        let mut score = *self.probe(chessboard);
+
+        for color in [Color::White, Color::Black] {
+            let sign = if color == Color::White { 1 } else { -1 };
+            let enemies = chessboard.get_color_pieces(color.swap());
+
+            let mut passed = score.passed[color as usize];
+            while passed != 0 {
+                let square = passed.trailing_zeros();
+                passed &= passed - 1;
+
+                let (mut bonus_mg, mut bonus_eg) = PASSED[relative_rank(color, square)];
+                if enemies & (1 << square_ahead(color, square)) != 0 {
+                    bonus_mg /= 2;
+                    bonus_eg /= 2;
+                }
+                score.add(sign, (bonus_mg, bonus_eg));
+            }
+
+            score.add(sign, (Self::shield(chessboard, color), 0));
+        }
+
+        (score.mg, score.eg)
+    }
+
+    /// Returns the midgame score of the pawns sheltering the king of `color`, on its file and the adjacent ones.
+    fn shield(chessboard: &Chessboard, color: Color) -> i32 {
+        let king = chessboard.get_piece(color, Piece::King).trailing_zeros();
+        if king >= 64 {
+            return 0;
+        }
+        let own = chessboard.get_piece(color, Piece::Pawn);
+        let rank = rank_mask(king);
+        let (close, far) = match color {
+            Color::White => (rank << 8, rank << 16),
+            Color::Black => (rank >> 8, rank >> 16),
+        };
+
+        let mut score = 0;
+        for file in (king % 8).saturating_sub(1)..=(king % 8 + 1).min(7) {
+            let file = FILE_A << file;
+            score += if own & file & close != 0 {
+                SHIELD_CLOSE
+            } else if own & file & far != 0 {
+                SHIELD_FAR
+            } else if own & file & ranks_ahead(color, king) == 0 {
+                SHIELD_MISSING
+            } else {
+                0
+            };
+        }
+        score
+    }
+}
@@ -0,0 +1,51 @@
+use crate::engine::{models::r#move::Move, search::MAX_PLY};
+
+/// Triangular table of principal variations.
+///
+/// Row `ply` holds the best line found from the node searched at `ply`, made of its best move followed by the
+/// row `ply + 1` of its best child. Row `0` is the principal variation of the whole search. Rows are allocated
+/// once, a search only clears and refills them.
+#[derive(Clone)]
+pub struct PvTable {
+    /// Best line of each ply, a row never holding more than `MAX_PLY - ply` moves.
+    lines: Vec<Vec<Move>>,
+}
+
+impl Default for PvTable {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PvTable {
+    /// Creates an empty table.
+    pub fn new() -> Self {
+        Self {
+            lines: (0..=MAX_PLY).map(|ply| Vec::with_capacity(MAX_PLY - ply)).collect(),
+        }
+    }
+
+    /// Clears the line of `ply`, once a node at this ply is entered.
+    #[inline]
+    pub fn clear(&mut self, ply: usize) {
+        self.lines[ply].clear();
+    }
+
+    /// Sets the line of `ply` to `mv` followed by the line of `ply + 1`, once `mv` becomes the best move at this ply.
+    #[inline]
+    pub fn update(&mut self, ply: usize, mv: &Move) {
+        let (line, rest) = self.lines[ply..].split_at_mut(1);
+        let line = &mut line[0];
+        line.clear();
+        line.push(mv.clone());
+        if let Some(child_line) = rest.first() {
+            line.extend_from_slice(child_line);
+        }
+    }
+
+    /// Returns the line of `ply`.
+    #[inline]
+    pub fn line(&self, ply: usize) -> &[Move] {
+        &self.lines[ply]
+    }
+}
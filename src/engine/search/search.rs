@@ -1,34 +1,122 @@
-use std::{cmp::max, collections::HashMap, sync::{Arc, Mutex}};
+use std::{cmp::max, sync::{Arc, atomic::{AtomicBool, AtomicU64, Ordering}}, thread, time::Duration};
 
-use crate::engine::{models::{board::{Chessboard, Color}, r#move::Move}, movegen::generate_legal_moves, search::evaluation::Evaluation};
-use rayon::prelude::*;
+use crate::engine::{models::{board::{Chessboard, Color}, piece::Piece, r#move::Move}, movegen::{generate_legal_captures, generate_legal_moves}, search::{evaluation::{Evaluation, INF, MATE}, limits::SearchLimits, ordering::{MAX_HISTORY, MoveOrdering, is_quiet}, pawns::PawnTable, pv::PvTable, selectivity::{Selectivity, late_move_reduction}, time::{DEFAULT_MOVE_OVERHEAD, TimeManager}, tt::{DEFAULT_HASH_SIZE, NodeType, TTEntry, TranspositionTable, score_from_tt, score_to_tt}}};
 
-#[derive(Default, Clone)]
-enum NodeType {
-    Exact,
-    Lowerbound,
-    Upperbound,
-    #[default]
-    None
-}
+/// Maximum depth of a search without a depth limit, i.e. a timed or infinite one.
+pub const MAX_DEPTH: i32 = 64;
 
-#[derive(Default, Clone)]
-pub struct TTEntry {
-    flag: NodeType,
-    depth: i32,
-    value: i32
-}
+/// Lowest score of a mate within [MAX_PLY] plies, any score beyond it is a mate.
+pub const MATE_IN_MAX_PLY: i32 = MATE - MAX_PLY as i32;
 
-impl TTEntry {
-    pub fn new() -> Self {
-        Self {..Default::default()}
-    }
+/// Default value of the UCI `Threads` option.
+pub const DEFAULT_THREADS: usize = 1;
+
+/// Maximum number of plies from the root, deeper nodes are evaluated right away.
+pub const MAX_PLY: usize = 128;
+
+/// Margin of the delta pruning of the quiescence search, in pawns.
+const DELTA_MARGIN: i32 = 2;
+
+/// Half-width of the first aspiration window around the score of the previous iteration, in pawns.
+const ASPIRATION_WINDOW: i32 = 1;
+
+/// Depth from which iterations are searched within an aspiration window, shallower ones being too unstable.
+const ASPIRATION_DEPTH: i32 = 4;
+
+/// Minimum depth of a node to try a null move.
+const NULL_MOVE_DEPTH: i32 = 3;
+
+/// Depth reduction of the search after a null move, on top of the move itself, growing by one every 4 plies.
+const NULL_MOVE_REDUCTION: i32 = 2;
+
+/// Maximum depth of a node pruned by (reverse) futility.
+const FUTILITY_DEPTH: i32 = 3;
+
+/// Futility margin per ply of depth left, in pawns.
+const FUTILITY_MARGIN: i32 = 2;
+
+/// Minimum depth of a node to reduce its late moves.
+const LMR_DEPTH: i32 = 3;
+
+/// Number of moves of a node searched at full depth before reducing the others.
+const LMR_MOVES: usize = 3;
+
+/// Maximum depth of a node pruned by late move pruning.
+const LMP_DEPTH: i32 = 3;
+
+/// Number of quiet moves of a node searched before pruning the others, plus the square of the depth left.
+const LMP_MOVES: usize = 3;
+
+/// Minimum depth of a node to try a singular extension of its hash move.
+const SINGULAR_DEPTH: i32 = 6;
+
+/// Margin by which the hash move must beat every other move to be singular, per ply of depth, in quarters of a pawn.
+const SINGULAR_MARGIN: i32 = 1;
+
+/// Number of nodes searched between two checks of the clock.
+const NODES_BETWEEN_TIME_CHECKS: u64 = 1024;
+
+/// Outcome of [Search::think].
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct SearchResult {
+    /// Best move found, `None` only if the position has no legal move.
+    pub best_move: Option<Move>,
+    /// Expected reply to the best move, to ponder on.
+    pub ponder_move: Option<Move>,
+    /// Score of the best move for the side to move, see [uci_score].
+    pub score: i32,
+    /// Last completed depth.
+    pub depth: i32,
+    /// Principal variation, starting with the best move.
+    pub pv: Vec<Move>,
 }
 
 #[derive(Default, Clone)]
 pub struct Search {
+    /// Maximum depth of the iterative deepening, in plies from the root.
     pub depth: i32,
-    pub tt: HashMap<u64, TTEntry> // zobrist_key, TTEntry
+    /// Transposition table, shared by every clone of this search.
+    pub tt: Arc<TranspositionTable>,
+    /// Limits of the next search, from the UCI `go` command.
+    pub limits: SearchLimits,
+    /// Time kept aside for the communication with the GUI, from the UCI `Move Overhead` option.
+    pub move_overhead: Duration,
+    /// Number of threads searching, from the UCI `Threads` option.
+    pub threads: usize,
+    /// Pruning and reduction techniques enabled, from the UCI options of the same names.
+    pub selectivity: Selectivity,
+    /// Set to interrupt the running search, shared by every clone of this search.
+    stop: Arc<AtomicBool>,
+    /// Set while the GUI ponders, the clock only starts once it is cleared by `ponderhit`.
+    pondering: Arc<AtomicBool>,
+    /// Set once the running search reached its node limit or its hard deadline.
+    aborted: bool,
+    /// Whether the deadlines of the running search are counted from now, they are delayed while pondering.
+    clock_started: bool,
+    /// Whether UCI `info` lines are printed while searching.
+    pub print_info: bool,
+    /// Number of nodes searched since the search started.
+    nodes: u64,
+    /// Copy of `nodes` updated every few nodes, read by the main thread while this search is a helper.
+    published_nodes: Arc<AtomicU64>,
+    /// Nodes published by the helpers of the running search.
+    helpers_nodes: Vec<Arc<AtomicU64>>,
+    /// Highest ply reached since the search started.
+    seldepth: usize,
+    /// Principal variations of the running search.
+    pv_table: PvTable,
+    /// Killer moves and history of the running search.
+    ordering: MoveOrdering,
+    /// Pawn structures scored by this thread, kept from one search to the next.
+    pawns: PawnTable,
+    /// Whether the move leading to each ply is a null move, two null moves in a row proving nothing.
+    null_moves: Vec<bool>,
+    /// Move left out of the node at each ply while checking whether it is singular, `0` if none.
+    excluded_moves: Vec<u16>,
+    /// Depth of the running iteration, nodes are only extended up to twice as many plies from the root.
+    root_depth: i32,
+    /// Deadlines of the running search.
+    time: Option<TimeManager>,
 }
 
 impl Search {
@@ -36,83 +124,579 @@ impl Search {
         println!("search created");
         Self { 
             depth,
+            move_overhead: Duration::from_millis(DEFAULT_MOVE_OVERHEAD),
+            threads: DEFAULT_THREADS,
+            tt: Arc::new(TranspositionTable::new(DEFAULT_HASH_SIZE)),
             ..Default::default()
         }
     }
 
-    fn negamax(&mut self, chessboard: &mut Chessboard, depth: i32, mut alpha: i32, beta: i32, color: i32) -> i32 {
+    /// Returns the flag interrupting the search once set, i.e. from another thread.
+    /// 
+    /// The search never clears it, it is up to the one setting it to clear it before the next search.
+    pub fn stop_flag(&self) -> Arc<AtomicBool> {
+        Arc::clone(&self.stop)
+    }
+
+    /// Returns the flag telling the search the GUI is pondering, its clock starts once the flag is cleared.
+    pub fn ponder_flag(&self) -> Arc<AtomicBool> {
+        Arc::clone(&self.pondering)
+    }
+
+    /// Checks if the search has been asked to stop, or ran out of nodes or time.
+    #[inline]
+    fn should_stop(&self) -> bool {
+        self.aborted || self.stop.load(Ordering::Relaxed)
+    }
+
+    /// Counts a new node, and aborts the search once the node limit or the hard deadline is reached.
+    #[inline]
+    fn count_node(&mut self) {
+        self.nodes += 1;
+
+        let out_of_nodes = self.limits.nodes.is_some_and(|nodes| self.nodes >= nodes);
+        let mut out_of_time = false;
+        if self.nodes.is_multiple_of(NODES_BETWEEN_TIME_CHECKS) {
+            self.published_nodes.store(self.nodes, Ordering::Relaxed);
+            out_of_time = self.deadline_reached(TimeManager::hard_deadline_reached);
+        }
+        if out_of_nodes || out_of_time {
+            self.aborted = true;
+        }
+    }
+
+    /// Checks a deadline of the running search, never reached while the GUI ponders.
+    fn deadline_reached(&mut self, deadline: fn(&TimeManager) -> bool) -> bool {
+        if self.pondering.load(Ordering::Relaxed) {
+            return false;
+        }
+        let Some(time) = self.time.as_mut() else {
+            return false;
+        };
+        // `ponderhit`, the time spent pondering was free
+        if !self.clock_started {
+            time.restart();
+            self.clock_started = true;
+        }
+        deadline(time)
+    }
+
+    /// Returns the number of nodes searched since the search started, by every thread.
+    pub fn nodes(&self) -> u64 {
+        self.nodes + self.helpers_nodes.iter().map(|nodes| nodes.load(Ordering::Relaxed)).sum::<u64>()
+    }
+
+    /// Returns how full the transposition table is, in permill.
+    pub fn hashfull(&self) -> usize {
+        self.tt.hashfull()
+    }
+
+    /// Evaluates `chessboard` from white's point of view, with the pawn hash table of this search.
+    pub fn evaluate(&mut self, chessboard: &Chessboard) -> i32 {
+        Evaluation::evaluate(chessboard, &mut self.pawns)
+    }
+
+    /// Replaces the transposition table by an empty one of `megabytes`, from the UCI `Hash` option.
+    pub fn set_hash_size(&mut self, megabytes: usize) {
+        self.tt = Arc::new(TranspositionTable::new(megabytes));
+    }
+
+    /// Searches `chessboard` at `depth`, `ply` plies below the root, the best line found being kept in the PV table.
+    /// 
+    /// Forcing lines are extended, up to twice the depth of the iteration: nodes in check by one ply, and the hash
+    /// move by one ply when it is singular, i.e. when every other move fails low by a margin on a reduced search.
+    fn negamax(&mut self, chessboard: &mut Chessboard, mut depth: i32, ply: usize, mut alpha: i32, beta: i32, color: i32) -> i32 {
         let alpha_orig = alpha;
+        self.pv_table.clear(ply);
+
+        // The score of an interrupted search is thrown away by `think`, any value will do.
+        if self.should_stop() {
+            return 0;
+        }
+        self.count_node();
+        self.seldepth = self.seldepth.max(ply);
+
+        // Every node reached here is below the root, a repeated position or an exhausted fifty-move counter is a draw.
+        if chessboard.is_repetition() || chessboard.is_fifty_move_draw() {
+            return 0;
+        }
         
-        if let Some(tt_entry) = self.tt.get(&chessboard.state.zobrist_key) && tt_entry.depth >= depth {
-            match tt_entry.flag {
-                NodeType::Exact => return tt_entry.value,
-                NodeType::Lowerbound if tt_entry.value >= beta => return tt_entry.value,
-                NodeType::Upperbound if tt_entry.value <= alpha => return tt_entry.value,
-                _ => ()
+        let excluded = self.excluded_moves[ply];
+        let in_check = chessboard.in_check();
+        let can_extend = (ply as i32) < 2 * self.root_depth;
+        if in_check && can_extend {
+            depth += 1;
+        }
+
+        let mut tt_move = 0;
+        let tt_entry = self.tt.probe(chessboard.state.zobrist_key);
+        if let Some(tt_entry) = tt_entry {
+            // a search without the excluded move is not the one stored
+            let value = score_from_tt(tt_entry.value, ply);
+            if excluded == 0 && tt_entry.depth >= depth {
+                match tt_entry.flag {
+                    NodeType::Exact => return value,
+                    NodeType::Lowerbound if value >= beta => return value,
+                    NodeType::Upperbound if value <= alpha => return value,
+                    _ => ()
+                }
             }
+            tt_move = tt_entry.best_move;
+        }
+
+        if depth == 0 || ply >= MAX_PLY {
+            return self.quiescence(chessboard, ply, alpha, beta, color);
+        }
+
+        let side = chessboard.state.turn_color;
+        let pv_node = beta - alpha > 1;
+        let static_eval = if in_check { -INF } else { color * Evaluation::evaluate(chessboard, &mut self.pawns) };
+        let pawn = Evaluation::piece_value(Piece::Pawn);
+        let shallow = !pv_node && !in_check && excluded == 0 && depth <= FUTILITY_DEPTH;
+
+        // Reverse futility pruning: far enough above beta, the opponent has already avoided this node.
+        if self.selectivity.reverse_futility && shallow && beta.abs() < MATE_IN_MAX_PLY && static_eval - FUTILITY_MARGIN * pawn * depth >= beta {
+            return static_eval;
         }
 
-        if depth == 0 {
-            return color * Evaluation::evaluate(chessboard);
+        // Null-move pruning: if passing the turn still fails high, a real move would too. Without pieces, zugzwangs
+        // make passing better than any move, so pawn endings are left out.
+        let has_pieces = [Piece::Knight, Piece::Bishop, Piece::Rook, Piece::Queen].into_iter().any(|piece| chessboard.get_piece(side, piece) != 0);
+        if self.selectivity.null_move && !pv_node && !in_check && excluded == 0 && depth >= NULL_MOVE_DEPTH && static_eval >= beta && has_pieces && !self.null_moves[ply] {
+            let reduction = NULL_MOVE_REDUCTION + depth / 4;
+            chessboard.make_null();
+            self.null_moves[ply + 1] = true;
+            let score = -self.negamax(chessboard, (depth - 1 - reduction).max(0), ply + 1, -beta, -beta + 1, -color);
+            self.null_moves[ply + 1] = false;
+            chessboard.unmake_null();
+
+            if self.should_stop() {
+                return 0;
+            }
+            if score >= beta {
+                // a mate found after passing the turn is not a mate
+                return if score >= MATE_IN_MAX_PLY { beta } else { score };
+            }
         }
 
+        // Futility pruning: quiet moves can't raise a score this far below alpha.
+        let futile = self.selectivity.futility && shallow && static_eval + FUTILITY_MARGIN * pawn * depth <= alpha;
+
         let child_nodes = generate_legal_moves(chessboard);
+        // Checkmated, the later the better, or stalemate.
+        if child_nodes.is_empty() {
+            return if in_check { -MATE + ply as i32 } else { 0 };
+        }
+        // matched against the legal moves, the entry might belong to another position with the same index
+        let tt_move = child_nodes.iter().find(|mv| mv.word == tt_move).cloned();
+        let child_nodes = self.ordering.picker(child_nodes, tt_move.as_ref(), side, ply);
+
+        // Singular extension: the hash move is searched deeper if no other move comes close to its stored score.
+        let mut singular_extension = 0;
+        if let Some(tt_entry) = tt_entry
+            && tt_move.is_some()
+            && excluded == 0
+            && can_extend
+            && depth >= SINGULAR_DEPTH
+            && tt_entry.depth >= depth - 3
+            && tt_entry.flag != NodeType::Upperbound
+            && tt_entry.value.abs() < MATE_IN_MAX_PLY
+        {
+            let singular_beta = tt_entry.value - (SINGULAR_MARGIN * pawn * depth / 4).max(1);
+            self.excluded_moves[ply] = tt_entry.best_move;
+            let score = self.negamax(chessboard, (depth - 1) / 2, ply, singular_beta - 1, singular_beta, color);
+            self.excluded_moves[ply] = 0;
+
+            if self.should_stop() {
+                return 0;
+            }
+            if score < singular_beta {
+                singular_extension = 1;
+            }
+        }
         
-        let mut best_score = i32::MIN;
-        for child in &child_nodes {
-            chessboard.make(child);
-            best_score = max(best_score, self.negamax(chessboard, depth - 1, beta.saturating_neg(), alpha.saturating_neg(), -color).saturating_neg());
-            chessboard.unmake(child);
+        let mut best_score = -INF;
+        let mut best_move = 0;
+        let mut quiets_tried = Vec::new();
+        let mut quiets_seen = 0;
+        for (index, child) in child_nodes.enumerate() {
+            if child.word == excluded {
+                continue;
+            }
+            chessboard.make(&child);
+            // moves giving check are never reduced nor pruned
+            let quiet = is_quiet(&child) && !chessboard.in_check();
 
-            alpha = max(alpha, best_score);
+            if index > 0 && quiet {
+                quiets_seen += 1;
+                let late = self.selectivity.late_move_pruning && shallow && depth <= LMP_DEPTH
+                    && quiets_seen > LMP_MOVES + (depth * depth) as usize;
+                if futile || late {
+                    chessboard.unmake(&child);
+                    continue;
+                }
+            }
+
+            // Late move reductions: the later a quiet move, the less likely it is to be best, unless it has a good history.
+            let reduction = if index == 0 {
+                None
+            } else if self.selectivity.late_move_reductions && quiet && !in_check && depth >= LMR_DEPTH && index >= LMR_MOVES {
+                let mut reduction = late_move_reduction(depth, index);
+                reduction -= i32::from(pv_node);
+                reduction -= self.ordering.history(&child, side) * 2 / MAX_HISTORY;
+                Some(reduction.clamp(0, depth - 2))
+            } else {
+                Some(0)
+            };
+
+            let extension = if tt_move.as_ref() == Some(&child) { singular_extension } else { 0 };
+            let score = self.search_child(chessboard, depth - 1 + extension, ply + 1, alpha, beta, -color, reduction);
+            chessboard.unmake(&child);
+
+            // Don't go on, nor store a score computed from interrupted children.
+            if self.should_stop() {
+                return 0;
+            }
+
+            if score > best_score {
+                best_score = score;
+                best_move = child.word;
+            }
+            if score > alpha {
+                alpha = score;
+                self.pv_table.update(ply, &child);
+            }
             if alpha >= beta {
+                if is_quiet(&child) {
+                    self.ordering.update(&child, &quiets_tried, side, ply, depth);
+                }
                 break;
             }
+            if is_quiet(&child) {
+                quiets_tried.push(child);
+            }
+        }
+
+        let flag = if best_score <= alpha_orig {
+            NodeType::Upperbound
+        } else if best_score >= beta {
+            NodeType::Lowerbound
+        } else {
+            NodeType::Exact
+        };
+        if excluded == 0 {
+            self.tt.store(chessboard.state.zobrist_key, TTEntry { flag, depth, value: score_to_tt(best_score, ply), best_move });
         }
 
-        let mut tt_entry = TTEntry::new();
-        if best_score <= alpha_orig {
-            tt_entry.flag = NodeType::Upperbound;
+        best_score
+    }
+
+    /// Searches the child reached by the last move made on `chessboard`, returning its score for the parent.
+    /// 
+    /// Principal Variation Search: only the first child, with no `reduction`, is expected to be within the window.
+    /// The others are searched with a null window around `alpha`, `reduction` plies shallower, just proving they are
+    /// no better. They are searched again at full depth, then with the full window, when they turn out to be.
+    #[allow(clippy::too_many_arguments, reason="Same node and window as negamax, plus the reduction")]
+    fn search_child(&mut self, chessboard: &mut Chessboard, depth: i32, ply: usize, alpha: i32, beta: i32, color: i32, reduction: Option<i32>) -> i32 {
+        if let Some(reduction) = reduction {
+            let mut score = -self.negamax(chessboard, depth - reduction, ply, -alpha - 1, -alpha, color);
+            if reduction > 0 && score > alpha {
+                score = -self.negamax(chessboard, depth, ply, -alpha - 1, -alpha, color);
+            }
+            if score <= alpha || score >= beta {
+                return score;
+            }
         }
-        else if best_score >= beta {
-            tt_entry.flag = NodeType::Lowerbound;
+        -self.negamax(chessboard, depth, ply, -beta, -alpha, color)
+    }
+
+    /// Searches the captures and promotions of `chessboard` until the position is quiet, so that a line is never
+    /// evaluated halfway through an exchange.
+    /// 
+    /// The side to move may stand pat, i.e. keep the static evaluation, when not in check. Captures that can't raise
+    /// the score up to `alpha`, even with a margin of [DELTA_MARGIN] pawns, are pruned.
+    fn quiescence(&mut self, chessboard: &mut Chessboard, ply: usize, mut alpha: i32, beta: i32, color: i32) -> i32 {
+        if self.should_stop() {
+            return 0;
         }
-        else {
-            tt_entry.flag = NodeType::Exact;
+        self.count_node();
+        self.seldepth = self.seldepth.max(ply);
+        self.pv_table.clear(ply);
+
+        if ply >= MAX_PLY {
+            return color * Evaluation::evaluate(chessboard, &mut self.pawns);
         }
 
-        tt_entry.depth = depth;
-        tt_entry.value = best_score;
-        self.tt.insert(chessboard.state.zobrist_key, tt_entry);
+        // Every evasion is searched when in check, standing pat is not an option.
+        let in_check = chessboard.in_check();
+        let mut best_score = -INF;
+        let moves = if in_check {
+            let evasions = generate_legal_moves(chessboard);
+            if evasions.is_empty() {
+                return -MATE + ply as i32;
+            }
+            evasions
+        } else {
+            best_score = color * Evaluation::evaluate(chessboard, &mut self.pawns);
+            if best_score >= beta {
+                return best_score;
+            }
+            alpha = max(alpha, best_score);
+            generate_legal_captures(chessboard)
+        };
+        let moves = self.ordering.picker(moves, None, chessboard.state.turn_color, ply);
+
+        let stand_pat = best_score;
+        let delta_margin = DELTA_MARGIN * Evaluation::piece_value(Piece::Pawn);
+        for mv in moves {
+            let promotion = mv.promotion_piece();
+            // Under-promotions hardly ever matter outside of the main search.
+            if !in_check && promotion.is_some_and(|piece| piece != Piece::Queen) {
+                continue;
+            }
+
+            if !in_check {
+                let mut gain = mv.captured_piece.map_or(0, Evaluation::piece_value);
+                if promotion.is_some() {
+                    gain += Evaluation::piece_value(Piece::Queen) - Evaluation::piece_value(Piece::Pawn);
+                }
+                if stand_pat + gain + delta_margin <= alpha {
+                    continue;
+                }
+            }
+
+            chessboard.make(&mv);
+            let score = -self.quiescence(chessboard, ply + 1, -beta, -alpha, -color);
+            chessboard.unmake(&mv);
+
+            if self.should_stop() {
+                return 0;
+            }
+
+            best_score = max(best_score, score);
+            if score > alpha {
+                alpha = score;
+                self.pv_table.update(ply, &mv);
+            }
+            if alpha >= beta {
+                break;
+            }
+        }
 
         best_score
     }
 
-    pub(crate) fn think(&mut self, chessboard: &mut Chessboard) -> Option<Move> {
-        let all_moves = generate_legal_moves(chessboard);
-        
-        let best_move= Arc::new(Mutex::new(None));
-        let best_score = Arc::new(Mutex::new(i32::MIN));
-        let all_moves = all_moves.as_slice();
-        all_moves.par_iter().for_each(|_move| {
-            let mut game_copy = chessboard.clone();
-            let mut search_copy = self.clone();
-
-            game_copy.make(_move);
-            let color = match game_copy.state.turn_color {
-                Color::White => 1,
-                Color::Black => -1,
-            };
-            let move_score = search_copy.negamax(&mut game_copy, search_copy.depth, i32::MIN, i32::MAX, color).saturating_neg();
-            game_copy.unmake(_move);
-            
-            let mut score_guard = best_score.lock().unwrap();
-            if move_score > *score_guard {
-                *score_guard = move_score;
-                *best_move.lock().unwrap() = Some(_move);
+    /// Searches the best move of `chessboard` by iterative deepening, from depth 1 up to the depth limit.
+    /// 
+    /// The search is bounded by [Search::limits]: without a depth limit, it goes up to [Search::depth] or, if timed,
+    /// to [MAX_DEPTH] until the [TimeManager] deadlines are reached.
+    /// 
+    /// Each iteration searches the best move of the previous one first, moves below the root being ordered by
+    /// [MoveOrdering]. The search can be interrupted at any time
+    /// through [Search::stop_flag], the result of the last completed depth is then returned.
+    /// 
+    /// While the [Search::ponder_flag] is set, the deadlines are ignored and only start counting once it is cleared.
+    /// 
+    /// With more than one of [Search::threads], helper threads search the same position alongside, only the result
+    /// of the calling thread being returned.
+    pub fn think(&mut self, chessboard: &mut Chessboard) -> SearchResult {
+        self.aborted = false;
+        self.nodes = 0;
+        self.seldepth = 0;
+        self.time = Some(TimeManager::new(&self.limits, chessboard.state.turn_color, self.move_overhead));
+        self.clock_started = !self.pondering.load(Ordering::Relaxed);
+        self.ordering.clear();
+        self.null_moves = vec![false; MAX_PLY + 1];
+        self.excluded_moves = vec![0; MAX_PLY + 1];
+        self.tt.new_search();
+
+        let unbounded = self.limits.is_timed() || self.limits.infinite || self.limits.nodes.is_some();
+        let max_depth = self.limits.depth.unwrap_or(if unbounded { MAX_DEPTH } else { self.depth });
+
+        // Lazy SMP: the helpers search the same root until this thread is done, sharing what they find through the
+        // transposition table. Every other helper starts one depth deeper, so that they don't all search in lockstep.
+        let helpers_stop = Arc::new(AtomicBool::new(false));
+        let helpers: Vec<Search> = (1..self.threads.max(1)).map(|_| self.helper(&helpers_stop)).collect();
+        self.helpers_nodes = helpers.iter().map(|helper| Arc::clone(&helper.published_nodes)).collect();
+
+        let mut result = thread::scope(|scope| {
+            for (index, mut helper) in helpers.into_iter().enumerate() {
+                let mut chessboard = chessboard.clone();
+                scope.spawn(move || helper.iterate(&mut chessboard, 1 + index as i32 % 2, MAX_DEPTH));
             }
+
+            let result = self.iterate(chessboard, 1, max_depth);
+            helpers_stop.store(true, Ordering::Relaxed);
+            result
         });
 
-        best_move.lock().unwrap().cloned()
+        if let Some(best_move) = &result.best_move {
+            result.ponder_move = match result.pv.get(1) {
+                Some(reply) => Some(reply.clone()),
+                None => self.guess_ponder_move(chessboard, best_move),
+            };
+        }
+        result
+    }
+
+    /// Returns a helper thread of the running search, searching without limits until `stop` is set.
+    fn helper(&self, stop: &Arc<AtomicBool>) -> Search {
+        Search {
+            limits: SearchLimits { infinite: true, ..Default::default() },
+            stop: Arc::clone(stop),
+            print_info: false,
+            threads: 1,
+            time: None,
+            published_nodes: Arc::default(),
+            helpers_nodes: Vec::new(),
+            ..self.clone()
+        }
     }
-}
\ No newline at end of file
+
+    /// Deepens the search of `chessboard` from `first_depth` up to `max_depth`, returning the result of the last
+    /// completed depth.
+    fn iterate(&mut self, chessboard: &mut Chessboard, first_depth: i32, max_depth: i32) -> SearchResult {
+        // Captures first for the first iteration, then the best move of the previous one.
+        let mut root_moves: Vec<Move> = self.ordering.picker(generate_legal_moves(chessboard), None, chessboard.state.turn_color, 0).collect();
+        // Any legal move is better than none if even the first iteration is interrupted.
+        let mut result = SearchResult {
+            best_move: root_moves.first().cloned(),
+            pv: root_moves.first().cloned().into_iter().collect(),
+            ..Default::default()
+        };
+
+        for depth in first_depth..=max_depth.clamp(first_depth, MAX_DEPTH) {
+            if let Some(best) = &result.best_move && let Some(index) = root_moves.iter().position(|mv| mv == best) {
+                root_moves[..=index].rotate_right(1);
+            }
+
+            let Some(score) = self.search_aspiration(chessboard, &root_moves, depth, result.score) else {
+                break;
+            };
+            let pv = self.pv_table.line(0);
+            self.report(depth, score, pv);
+            result.best_move = pv.first().cloned();
+            result.pv = pv.to_vec();
+            result.score = score;
+            result.depth = depth;
+
+            if self.deadline_reached(TimeManager::soft_deadline_reached) {
+                break;
+            }
+        }
+
+        self.published_nodes.store(self.nodes, Ordering::Relaxed);
+        result
+    }
+
+    /// Guesses the reply to `best_move` when the principal variation stops at it, i.e. the one giving the lowest
+    /// score to the side to move among the replies found in the transposition table.
+    /// `chessboard` is left unchanged once the function returns.
+    fn guess_ponder_move(&self, chessboard: &mut Chessboard, best_move: &Move) -> Option<Move> {
+        chessboard.make(best_move);
+        let mut ponder_move = None;
+        let mut lowest_score = i32::MAX;
+        for reply in generate_legal_moves(chessboard) {
+            chessboard.make(&reply);
+            // the entry is scored for the side to move after the reply, that is us
+            if let Some(tt_entry) = self.tt.probe(chessboard.state.zobrist_key) && (ponder_move.is_none() || tt_entry.value < lowest_score) {
+                lowest_score = tt_entry.value;
+                ponder_move = Some(reply.clone());
+            }
+            chessboard.unmake(&reply);
+        }
+        chessboard.unmake(best_move);
+
+        ponder_move
+    }
+
+    /// Searches the root at `depth` within a window around the `previous` score, widened until the score falls
+    /// inside it, returning `None` if the search was interrupted.
+    /// 
+    /// Shallow depths and mate scores are searched with the full window.
+    fn search_aspiration(&mut self, chessboard: &mut Chessboard, root_moves: &[Move], depth: i32, previous: i32) -> Option<i32> {
+        if depth < ASPIRATION_DEPTH || previous.abs() >= MATE_IN_MAX_PLY {
+            return self.search_root(chessboard, root_moves, depth, -INF, INF);
+        }
+
+        let mut delta = ASPIRATION_WINDOW * Evaluation::piece_value(Piece::Pawn);
+        let mut alpha = (previous - delta).max(-INF);
+        let mut beta = (previous + delta).min(INF);
+        loop {
+            let score = self.search_root(chessboard, root_moves, depth, alpha, beta)?;
+            if score <= alpha && alpha > -INF {
+                alpha = (alpha - delta).max(-INF);
+            } else if score >= beta && beta < INF {
+                beta = (beta + delta).min(INF);
+            } else {
+                return Some(score);
+            }
+            delta *= 2;
+        }
+    }
+
+    /// Searches every root move at `depth` within `alpha` and `beta`, in order, returning the score of the best one,
+    /// its principal variation being the line `0` of the PV table, or `None` if the search was interrupted.
+    fn search_root(&mut self, chessboard: &mut Chessboard, root_moves: &[Move], depth: i32, mut alpha: i32, beta: i32) -> Option<i32> {
+        let color = match chessboard.state.turn_color {
+            Color::White => 1,
+            Color::Black => -1,
+        };
+
+        self.root_depth = depth;
+        let mut best_score = -INF;
+        self.pv_table.clear(0);
+        for (index, mv) in root_moves.iter().enumerate() {
+            chessboard.make(mv);
+            let score = self.search_child(chessboard, depth - 1, 1, alpha, beta, -color, (index > 0).then_some(0));
+            chessboard.unmake(mv);
+
+            if self.should_stop() {
+                return None;
+            }
+            if index == 0 || score > best_score {
+                best_score = score;
+                self.pv_table.update(0, mv);
+                // a new best move after the one of the previous iteration, within the window
+                if index > 0 && score > alpha && score < beta {
+                    self.report(depth, score, self.pv_table.line(0));
+                }
+            }
+            alpha = alpha.max(score);
+            if alpha >= beta {
+                break;
+            }
+        }
+
+        (!root_moves.is_empty()).then_some(best_score)
+    }
+
+    /// Prints an UCI `info` line about the best line found so far, if [Search::print_info] is set.
+    fn report(&self, depth: i32, score: i32, pv: &[Move]) {
+        if !self.print_info {
+            return;
+        }
+
+        let time = self.time.map(|time| time.elapsed()).unwrap_or_default();
+        let nodes = self.nodes();
+        let nps = nodes as u128 * 1000 / time.as_millis().max(1);
+        let pv = pv.iter().map(Move::to_string).collect::<Vec<_>>();
+        println!(
+            "info depth {} seldepth {} score {} nodes {} nps {} time {} hashfull {} pv {}",
+            depth, self.seldepth.max(depth as usize), uci_score(score), nodes, nps, time.as_millis(), self.hashfull(), pv.join(" ")
+        );
+    }
+}
+
+/// Formats a score for UCI, `cp <score>` or `mate <moves>` with a negative number of moves when getting mated.
+/// 
+/// The distance to mate is read from the score, see [MATE].
+pub fn uci_score(score: i32) -> String {
+    if score >= MATE_IN_MAX_PLY {
+        format!("mate {}", (MATE - score + 1) / 2)
+    } else if score <= -MATE_IN_MAX_PLY {
+        format!("mate -{}", (MATE + score) / 2)
+    } else {
+        format!("cp {}", score)
+    }
+}
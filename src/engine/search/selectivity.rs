@@ -0,0 +1,52 @@
+use std::sync::OnceLock;
+
+use crate::engine::search::MAX_DEPTH;
+
+/// Switches of the pruning and reduction techniques of the search, all enabled by default.
+///
+/// Each technique can be turned off on its own, i.e. through its UCI option, to measure what it brings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Selectivity {
+    /// Skips a node when passing the turn still fails high on a reduced search.
+    pub null_move: bool,
+    /// Searches the late quiet moves of a node at a reduced depth first.
+    pub late_move_reductions: bool,
+    /// Skips the quiet moves of a shallow node whose evaluation is far below `alpha`.
+    pub futility: bool,
+    /// Cuts a shallow node whose evaluation is far above `beta`.
+    pub reverse_futility: bool,
+    /// Skips the quiet moves of a shallow node after the first few ones.
+    pub late_move_pruning: bool,
+}
+
+impl Default for Selectivity {
+    fn default() -> Self {
+        Self {
+            null_move: true,
+            late_move_reductions: true,
+            futility: true,
+            reverse_futility: true,
+            late_move_pruning: true,
+        }
+    }
+}
+
+/// Number of plies a late move is reduced by, from the `depth` of its node and its `index` among its siblings.
+///
+/// The reduction grows with the logarithm of both, so that the deeper the node and the later the move, the more it is
+/// reduced.
+pub fn late_move_reduction(depth: i32, index: usize) -> i32 {
+    static REDUCTIONS: OnceLock<Vec<[i32; 64]>> = OnceLock::new();
+    let reductions = REDUCTIONS.get_or_init(|| {
+        (0..=MAX_DEPTH as usize)
+            .map(|depth| {
+                std::array::from_fn(|index| match (depth, index) {
+                    (0, _) | (_, 0) => 0,
+                    _ => (0.75 + (depth as f64).ln() * (index as f64).ln() / 2.25) as i32,
+                })
+            })
+            .collect()
+    });
+
+    reductions[depth.clamp(0, MAX_DEPTH) as usize][index.min(63)]
+}
@@ -0,0 +1,93 @@
+use std::time::{Duration, Instant};
+
+use crate::engine::{models::board::Color, search::limits::SearchLimits};
+
+/// Default value of the UCI `Move Overhead` option, in milliseconds.
+pub const DEFAULT_MOVE_OVERHEAD: u64 = 30;
+
+/// Number of moves the remaining time is split into when the GUI doesn't send `movestogo`.
+const DEFAULT_MOVES_TO_GO: u32 = 30;
+
+/// Turns the clock state of a search into deadlines.
+///
+/// - the soft deadline is checked between two iterations, no new depth is started once it is reached.
+/// - the hard deadline is checked during the search, which is interrupted once it is reached.
+#[derive(Debug, Clone, Copy)]
+pub struct TimeManager {
+    /// When the search started.
+    start: Instant,
+    /// No new iteration starts after this instant.
+    soft_deadline: Option<Instant>,
+    /// The search stops at this instant.
+    hard_deadline: Option<Instant>,
+}
+
+impl TimeManager {
+    /// Computes the deadlines of a search starting now, for `color` to move.
+    ///
+    /// `move_overhead` is kept aside from the time left to cover the communication with the GUI.
+    pub fn new(limits: &SearchLimits, color: Color, move_overhead: Duration) -> Self {
+        let start = Instant::now();
+        let (soft, hard) = Self::allocate(limits, color, move_overhead).unzip();
+
+        Self {
+            start,
+            soft_deadline: soft.map(|soft| start + soft),
+            hard_deadline: hard.map(|hard| start + hard),
+        }
+    }
+
+    /// Returns the soft and hard time budgets, or `None` if the search is not timed.
+    fn allocate(limits: &SearchLimits, color: Color, move_overhead: Duration) -> Option<(Duration, Duration)> {
+        if limits.infinite {
+            return None;
+        }
+        if let Some(movetime) = limits.movetime {
+            let budget = movetime.saturating_sub(move_overhead);
+            return Some((budget, budget));
+        }
+
+        let (time, increment) = match color {
+            Color::White => (limits.wtime?, limits.winc.unwrap_or_default()),
+            Color::Black => (limits.btime?, limits.binc.unwrap_or_default()),
+        };
+        let remaining = time.saturating_sub(move_overhead);
+        let moves_to_go = limits.movestogo.map_or(DEFAULT_MOVES_TO_GO, |moves_to_go| moves_to_go.max(1));
+
+        // never use more than 80% of the clock on a single move
+        let maximum = remaining.mul_f64(0.8);
+        let soft = (remaining / moves_to_go + increment.mul_f64(0.75)).min(maximum);
+        let hard = (soft * 3).min(maximum);
+
+        Some((soft, hard))
+    }
+
+    /// Starts the clock again from now, keeping the same time budgets, i.e. once pondering is over.
+    pub fn restart(&mut self) {
+        let now = Instant::now();
+        let paused = now - self.start;
+        self.start = now;
+        self.soft_deadline = self.soft_deadline.map(|deadline| deadline + paused);
+        self.hard_deadline = self.hard_deadline.map(|deadline| deadline + paused);
+    }
+
+    /// Returns the time elapsed since the search started.
+    pub fn elapsed(&self) -> Duration {
+        self.start.elapsed()
+    }
+
+    /// Returns the time after which no new iteration starts, counted from the start of the search, if timed.
+    pub fn soft_budget(&self) -> Option<Duration> {
+        self.soft_deadline.map(|deadline| deadline - self.start)
+    }
+
+    /// Checks if no new iteration should be started.
+    pub fn soft_deadline_reached(&self) -> bool {
+        self.soft_deadline.is_some_and(|deadline| Instant::now() >= deadline)
+    }
+
+    /// Checks if the search must stop now.
+    pub fn hard_deadline_reached(&self) -> bool {
+        self.hard_deadline.is_some_and(|deadline| Instant::now() >= deadline)
+    }
+}
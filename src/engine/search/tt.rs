@@ -0,0 +1,224 @@
+use std::sync::atomic::{AtomicU8, AtomicU64, Ordering};
+
+use crate::engine::search::MATE_IN_MAX_PLY;
+
+/// Default value of the UCI `Hash` option, in megabytes.
+pub const DEFAULT_HASH_SIZE: usize = 16;
+
+/// Number of entries sharing a bucket, a bucket filling a cache line.
+const BUCKET_SIZE: usize = 4;
+
+/// Number of buckets sampled by [TranspositionTable::hashfull], i.e. 1000 entries.
+const HASHFULL_SAMPLE: usize = 1000 / BUCKET_SIZE;
+
+/// Converts a score found `ply` plies from the root into a score stored in the table.
+///
+/// Mate scores count the plies from the root, they are stored counting them from the position itself, which may be
+/// reached again at another ply.
+pub fn score_to_tt(score: i32, ply: usize) -> i32 {
+    if score >= MATE_IN_MAX_PLY {
+        score + ply as i32
+    } else if score <= -MATE_IN_MAX_PLY {
+        score - ply as i32
+    } else {
+        score
+    }
+}
+
+/// Converts a score stored in the table into a score `ply` plies from the root, see [score_to_tt].
+pub fn score_from_tt(score: i32, ply: usize) -> i32 {
+    if score >= MATE_IN_MAX_PLY {
+        score - ply as i32
+    } else if score <= -MATE_IN_MAX_PLY {
+        score + ply as i32
+    } else {
+        score
+    }
+}
+
+/// Bound of the score of an entry.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum NodeType {
+    /// No entry, never stored.
+    #[default]
+    None,
+    /// The score is exact, i.e. within the search window.
+    Exact,
+    /// The score failed high, the real one is at least this one.
+    Lowerbound,
+    /// The score failed low, the real one is at most this one.
+    Upperbound,
+}
+
+/// Result of the search of a position, as kept by the [TranspositionTable].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct TTEntry {
+    /// Bound of `value`.
+    pub flag: NodeType,
+    /// Depth of the search, entries searched deeper being more reliable.
+    pub depth: i32,
+    /// Score of the position for the side to move, mates counted from the position, see [score_to_tt].
+    pub value: i32,
+    /// Word of the best move found, `0` if none, searched first the next time the position is reached.
+    pub best_move: u16,
+}
+
+impl TTEntry {
+    /// Packs the entry into 64 bits, along with the `age` of the search storing it:
+    /// move (16 bits) | value (16 bits) | depth (8 bits) | flag (8 bits) | age (8 bits).
+    fn pack(&self, age: u8) -> u64 {
+        let value = self.value.clamp(-i16::MAX as i32, i16::MAX as i32) as i16 as u16;
+        self.best_move as u64
+            | (value as u64) << 16
+            | (self.depth.clamp(0, u8::MAX as i32) as u64) << 32
+            | (self.flag as u64) << 40
+            | (age as u64) << 48
+    }
+
+    /// Unpacks an entry packed by [TTEntry::pack], returning it with its age.
+    fn unpack(data: u64) -> (Self, u8) {
+        let flag = match (data >> 40) as u8 {
+            1 => NodeType::Exact,
+            2 => NodeType::Lowerbound,
+            3 => NodeType::Upperbound,
+            _ => NodeType::None,
+        };
+        let entry = Self {
+            flag,
+            depth: (data >> 32) as u8 as i32,
+            value: (data >> 16) as u16 as i16 as i32,
+            best_move: data as u16,
+        };
+        (entry, (data >> 48) as u8)
+    }
+}
+
+/// Slot of a bucket, the key being stored XORed with the data so that an entry torn by two threads writing it at
+/// once no longer matches any position.
+#[derive(Default)]
+struct Slot {
+    key: AtomicU64,
+    data: AtomicU64,
+}
+
+impl Slot {
+    /// Returns the data of the slot and the key it was stored for.
+    #[inline]
+    fn load(&self) -> (u64, u64) {
+        let data = self.data.load(Ordering::Relaxed);
+        (self.key.load(Ordering::Relaxed) ^ data, data)
+    }
+
+    #[inline]
+    fn store(&self, key: u64, data: u64) {
+        self.key.store(key ^ data, Ordering::Relaxed);
+        self.data.store(data, Ordering::Relaxed);
+    }
+}
+
+#[derive(Default)]
+#[repr(align(64))]
+struct Bucket([Slot; BUCKET_SIZE]);
+
+/// Transposition table of a fixed size, shared by the threads of a search without locks.
+///
+/// Positions are mapped to a bucket of [BUCKET_SIZE] entries by their Zobrist key. Once a bucket is full, the entry
+/// of the same position is replaced first, then the shallowest one, entries left by previous searches being
+/// considered shallower the older they are.
+#[derive(Default)]
+pub struct TranspositionTable {
+    buckets: Vec<Bucket>,
+    /// Age of the running search, increased by [TranspositionTable::new_search].
+    age: AtomicU8,
+}
+
+impl TranspositionTable {
+    /// Creates a table of `megabytes`, holding at least one bucket.
+    pub fn new(megabytes: usize) -> Self {
+        let count = (megabytes * 1024 * 1024 / size_of::<Bucket>()).max(1);
+        Self {
+            buckets: (0..count).map(|_| Bucket::default()).collect(),
+            age: AtomicU8::new(0),
+        }
+    }
+
+    /// Returns the number of entries of the table.
+    pub fn capacity(&self) -> usize {
+        self.buckets.len() * BUCKET_SIZE
+    }
+
+    /// Empties the table, i.e. on `ucinewgame`.
+    pub fn clear(&self) {
+        for slot in self.buckets.iter().flat_map(|bucket| &bucket.0) {
+            slot.key.store(0, Ordering::Relaxed);
+            slot.data.store(0, Ordering::Relaxed);
+        }
+        self.age.store(0, Ordering::Relaxed);
+    }
+
+    /// Ages the entries of the previous searches, once a new search starts.
+    pub fn new_search(&self) {
+        self.age.fetch_add(1, Ordering::Relaxed);
+    }
+
+    #[inline]
+    fn bucket(&self, key: u64) -> Option<&Bucket> {
+        // maps the key onto the buckets without requiring a power of two
+        let index = ((key as u128 * self.buckets.len() as u128) >> 64) as usize;
+        self.buckets.get(index)
+    }
+
+    /// Returns the entry stored for the position of Zobrist `key`, if any.
+    pub fn probe(&self, key: u64) -> Option<TTEntry> {
+        self.bucket(key)?.0.iter()
+            .map(Slot::load)
+            .find(|&(slot_key, data)| slot_key == key && data != 0)
+            .map(|(_, data)| TTEntry::unpack(data).0)
+    }
+
+    /// Stores `entry` for the position of Zobrist `key`, the best move of the previous entry of the position being
+    /// kept if `entry` has none.
+    pub fn store(&self, key: u64, mut entry: TTEntry) {
+        let Some(bucket) = self.bucket(key) else {
+            return;
+        };
+        let age = self.age.load(Ordering::Relaxed);
+
+        let mut replaced = &bucket.0[0];
+        let mut lowest_worth = i32::MAX;
+        for slot in &bucket.0 {
+            let (slot_key, data) = slot.load();
+            if data == 0 || slot_key == key {
+                if entry.best_move == 0 && slot_key == key {
+                    entry.best_move = TTEntry::unpack(data).0.best_move;
+                }
+                replaced = slot;
+                break;
+            }
+
+            let (stored, stored_age) = TTEntry::unpack(data);
+            let worth = stored.depth - 4 * age.wrapping_sub(stored_age) as i32;
+            if worth < lowest_worth {
+                lowest_worth = worth;
+                replaced = slot;
+            }
+        }
+
+        replaced.store(key, entry.pack(age));
+    }
+
+    /// Returns how full the table is in permill, counting the entries of the running search among the first ones.
+    pub fn hashfull(&self) -> usize {
+        let age = self.age.load(Ordering::Relaxed);
+        let sample = &self.buckets[..HASHFULL_SAMPLE.min(self.buckets.len())];
+        let used = sample.iter()
+            .flat_map(|bucket| &bucket.0)
+            .filter(|slot| {
+                let data = slot.data.load(Ordering::Relaxed);
+                data != 0 && TTEntry::unpack(data).1 == age
+            })
+            .count();
+
+        (used * 1000).checked_div(sample.len() * BUCKET_SIZE).unwrap_or_default()
+    }
+}
@@ -0,0 +1,80 @@
+#[cfg(test)]
+mod tests {
+    use lib::engine::models::{board::{Chessboard, Color}, r#move::Move, outcome::Outcome};
+
+    fn play(chessboard: &mut Chessboard, moves: &[&str]) {
+        for uci in moves {
+            let mv = Move::decode_uci(uci, chessboard).unwrap();
+            chessboard.make(&mv);
+        }
+    }
+
+    #[test]
+    fn test_threefold_repetition() {
+        let mut chessboard = Chessboard::new();
+        assert!(!chessboard.is_repetition());
+
+        play(&mut chessboard, &["g1f3", "g8f6", "f3g1", "f6g8"]);
+        assert!(chessboard.is_repetition());
+        assert!(!chessboard.is_threefold_repetition());
+
+        play(&mut chessboard, &["g1f3", "g8f6", "f3g1", "f6g8"]);
+        assert_eq!(chessboard.repetition_count(), 2);
+        assert!(chessboard.is_threefold_repetition());
+    }
+
+    #[test]
+    fn test_repetition_broken_by_pawn_move() {
+        let mut chessboard = Chessboard::new();
+        play(&mut chessboard, &["g1f3", "g8f6", "f3g1", "f6g8", "e2e4", "e7e5", "g1f3", "g8f6", "f3g1", "f6g8"]);
+
+        // the start position cannot be reached anymore, only the one after 1. e4 e5 repeats
+        assert_eq!(chessboard.repetition_count(), 1);
+    }
+
+    #[test]
+    fn test_fifty_move_draw() {
+        let mut chessboard = Chessboard::from_fen("4k3/8/8/8/8/8/8/R3K3 w - - 99 80").unwrap();
+        assert!(!chessboard.is_fifty_move_draw());
+
+        play(&mut chessboard, &["a1a2"]);
+        assert!(chessboard.is_fifty_move_draw());
+
+        let mut chessboard = Chessboard::from_fen("4k3/8/8/8/8/8/4P3/4K2R w K - 99 80").unwrap();
+        play(&mut chessboard, &["e2e3"]);
+        assert!(!chessboard.is_fifty_move_draw());
+    }
+
+    #[test]
+    fn test_outcome_checkmate_and_stalemate() {
+        let mut chessboard = Chessboard::new();
+        play(&mut chessboard, &["f2f3", "e7e5", "g2g4", "d8h4"]);
+        assert_eq!(chessboard.outcome(), Outcome::Checkmate { winner: Color::Black });
+
+        let mut chessboard = Chessboard::from_fen("7k/5Q2/6K1/8/8/8/8/8 b - - 0 1").unwrap();
+        assert_eq!(chessboard.outcome(), Outcome::Stalemate);
+
+        assert_eq!(Chessboard::new().outcome(), Outcome::Ongoing);
+    }
+
+    #[test]
+    fn test_outcome_insufficient_material() {
+        let draws = [
+            "4k3/8/8/8/8/8/8/4K3 w - - 0 1",
+            "4k3/8/8/8/8/8/8/4KN2 w - - 0 1",
+            "4kb2/8/8/8/8/8/8/2B1K3 w - - 0 1",
+        ];
+        for fen in draws {
+            assert_eq!(Chessboard::from_fen(fen).unwrap().outcome(), Outcome::InsufficientMaterial, "{}", fen);
+        }
+
+        let playable = [
+            "4k3/8/8/8/8/8/8/3NKN2 w - - 0 1",
+            "4k1b1/8/8/8/8/8/8/2B1K3 w - - 0 1",
+            "4k3/8/8/8/8/8/4P3/4K3 w - - 0 1",
+        ];
+        for fen in playable {
+            assert_eq!(Chessboard::from_fen(fen).unwrap().outcome(), Outcome::Ongoing, "{}", fen);
+        }
+    }
+}
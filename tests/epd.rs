@@ -0,0 +1,40 @@
+#[cfg(test)]
+mod tests {
+    use lib::engine::{models::{fen::FenError, r#move::MoveError}, notation::epd::{Epd, EpdError}};
+
+    #[test]
+    fn test_read_operations() {
+        let epd = Epd::from_epd(r#"r1bqk1nr/pppp1ppp/2n5/2b1p3/2B1P3/5Q2/PPPP1PPP/RNB1K1NR w KQkq - bm Qxf7#; am Qd1 Nh3; id "mate in one"; c0 "scholar's mate"; c3 "third"; hmvc 4; fmvn 4; acd 12; ce +32000;"#).unwrap();
+
+        assert_eq!(epd.id.as_deref(), Some("mate in one"));
+        assert_eq!(epd.best_moves.iter().map(|mv| mv.to_string()).collect::<Vec<_>>(), vec!["f3f7"]);
+        assert_eq!(epd.avoid_moves.iter().map(|mv| mv.to_string()).collect::<Vec<_>>(), vec!["f3d1", "g1h3"]);
+        assert_eq!(epd.comment(0), Some("scholar's mate"));
+        assert_eq!(epd.comment(3), Some("third"));
+        assert_eq!(epd.comment(1), None);
+        assert_eq!(epd.chessboard.to_fen(), "r1bqk1nr/pppp1ppp/2n5/2b1p3/2B1P3/5Q2/PPPP1PPP/RNB1K1NR w KQkq - 4 4");
+        assert_eq!(epd.operation("acd").unwrap().operands, vec!["12"]);
+        assert_eq!(epd.operation("ce").unwrap().operands, vec!["+32000"]);
+    }
+
+    #[test]
+    fn test_read_perft_counts() {
+        let epd = Epd::from_epd("4k3/8/8/8/8/8/8/4K2R w K - ;D1 15 ;D2 66 ;D3 1197").unwrap();
+
+        assert_eq!(epd.perft, vec![(1, 15), (2, 66), (3, 1197)]);
+        assert_eq!(epd.chessboard.to_fen(), "4k3/8/8/8/8/8/8/4K2R w K - 0 1");
+        assert!(epd.operations.is_empty());
+    }
+
+    #[test]
+    fn test_read_errors() {
+        assert!(matches!(Epd::from_epd("4k3/8/8/8/8/8/8/4K2R w"), Err(EpdError::FieldCount { found: 2 })));
+        assert!(matches!(Epd::from_epd("4k3/8/8/8/8/8/8/4K2R x K -"), Err(EpdError::InvalidFen(FenError::InvalidValue { .. }))));
+        assert!(matches!(Epd::from_epd(r#"4k3/8/8/8/8/8/8/4K2R w K - id "open"#), Err(EpdError::Unterminated)));
+        assert!(matches!(Epd::from_epd("4k3/8/8/8/8/8/8/4K2R w K - bm Ke3;"), Err(EpdError::InvalidMove { error: MoveError::Illegal(_), .. })));
+        assert!(matches!(Epd::from_epd("4k3/8/8/8/8/8/8/4K2R w K - D1 many;"), Err(EpdError::InvalidOperand { .. })));
+        assert!(matches!(Epd::from_epd("4k3/8/8/8/8/8/8/4K2R w K - id;"), Err(EpdError::MissingOperand { .. })));
+        assert!(matches!(Epd::from_epd("4k3/8/8/8/8/8/8/4K2R w K - 1x 2;"), Err(EpdError::InvalidOpcode { .. })));
+        assert!(matches!(Epd::parse_all("# suite\n\n4k3/8/8/8/8/8/8/4K2R w K -\n4k3/8/8/8/8/8/8/4K2R w"), Err(EpdError::Line { line: 4, .. })));
+    }
+}
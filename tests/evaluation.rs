@@ -0,0 +1,103 @@
+#[cfg(test)]
+mod tests {
+    use lib::engine::{
+        models::{board::Chessboard, psqt::{MAX_PHASE, PieceSquareScore}, r#move::Move},
+        search::{evaluation::Evaluation, pawns::{PawnEntry, PawnTable}},
+    };
+
+    #[test]
+    fn test_evaluation_start_position() {
+        let chessboard = Chessboard::new();
+        assert_eq!(chessboard.psqt().phase, MAX_PHASE);
+        assert_eq!(Evaluation::evaluate(&chessboard, &mut PawnTable::new()), 0);
+    }
+
+    #[test]
+    fn test_evaluation_mirrored() {
+        let white = Chessboard::from_fen("r1bqk2r/pppp1ppp/2n2n2/2b1p3/2B1P3/3P1N2/PPP2PPP/RNBQK2R b KQkq - 0 5").unwrap();
+        let black = Chessboard::from_fen("rnbqk2r/ppp2ppp/3p1n2/2b1p3/2B1P3/2N2N2/PPPP1PPP/R1BQK2R w KQkq - 0 5").unwrap();
+        let mut pawns = PawnTable::new();
+        assert_ne!(Evaluation::evaluate(&white, &mut pawns), 0);
+        assert_eq!(Evaluation::evaluate(&white, &mut pawns), -Evaluation::evaluate(&black, &mut pawns));
+    }
+
+    #[test]
+    fn test_evaluation_tapered() {
+        // a lone extra pawn is worth its endgame value once the pieces are off
+        let chessboard = Chessboard::from_fen("4k3/8/8/8/8/8/4P3/4K3 w - - 0 1").unwrap();
+        let score = chessboard.psqt();
+        let mut pawns = PawnTable::new();
+        let (_, pawns_eg) = pawns.evaluate(&chessboard);
+        assert_eq!(score.phase, 0);
+        assert_eq!(Evaluation::evaluate(&chessboard, &mut pawns), score.eg + pawns_eg);
+        assert_ne!(score.mg, score.eg);
+    }
+
+    #[test]
+    fn test_evaluation_incremental() {
+        let mut chessboard = Chessboard::from_fen("r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1").unwrap();
+        let initial = chessboard.psqt();
+        let mut played = Vec::new();
+        // an en passant capture after a double push, then a capture promoting to a queen
+        for uci in ["g1h1", "c7c5", "b5c6", "b2a1q"] {
+            let mv = Move::decode_uci(uci, &mut chessboard).unwrap();
+            chessboard.make(&mv);
+            assert_eq!(chessboard.psqt(), PieceSquareScore::compute(&chessboard), "after {}", uci);
+            assert_eq!(chessboard.pawn_key(), Chessboard::from_fen(&chessboard.to_fen()).unwrap().pawn_key(), "after {}", uci);
+            played.push(mv);
+        }
+        for mv in played.iter().rev() {
+            chessboard.unmake(mv);
+        }
+        assert_eq!(chessboard.psqt(), initial);
+    }
+
+    #[test]
+    fn test_pawn_key() {
+        // same pawns, other pieces and side to move
+        let first = Chessboard::from_fen("r1bqkbnr/pppp1ppp/2n5/4p3/4P3/5N2/PPPP1PPP/RNBQKB1R w KQkq - 2 3").unwrap();
+        let second = Chessboard::from_fen("rnbqkb1r/pppp1ppp/5n2/4p3/4P3/2N5/PPPP1PPP/R1BQKBNR b KQkq - 2 3").unwrap();
+        assert_eq!(first.pawn_key(), second.pawn_key());
+        assert_ne!(first.zobrist_key(), second.zobrist_key());
+
+        let pawnless = Chessboard::from_fen("4k3/8/8/8/8/8/8/R3K3 w - - 0 1").unwrap();
+        assert_eq!(pawnless.pawn_key(), 0);
+        assert_eq!(PawnTable::new().evaluate(&pawnless), (0, 0));
+    }
+
+    #[test]
+    fn test_pawn_structure() {
+        // the a pawns are isolated and doubled, the front one is passed, the h pawn is stopped by g7
+        let chessboard = Chessboard::from_fen("4k3/6p1/8/8/8/P7/P6P/4K3 w - - 0 1").unwrap();
+        let entry = PawnEntry::compute(&chessboard);
+        assert_eq!(entry.passed, [1 << 16, 0]);
+        assert!(entry.mg < 0 && entry.eg < 0);
+
+        // connected pawns beat the same pawns split apart
+        let connected = Chessboard::from_fen("4k3/8/8/8/8/8/PPP5/4K3 w - - 0 1").unwrap();
+        let split = Chessboard::from_fen("4k3/8/8/8/8/8/P1P1P3/4K3 w - - 0 1").unwrap();
+        assert!(PawnEntry::compute(&connected).mg > PawnEntry::compute(&split).mg);
+
+        // a passed pawn is worth less once blocked
+        let free = Chessboard::from_fen("4k3/8/8/3P4/8/8/8/4K3 w - - 0 1").unwrap();
+        let blocked = Chessboard::from_fen("8/8/3k4/3P4/8/8/8/4K3 w - - 0 1").unwrap();
+        assert_eq!(PawnEntry::compute(&free).passed, PawnEntry::compute(&blocked).passed);
+        assert!(PawnTable::new().evaluate(&free).1 > PawnTable::new().evaluate(&blocked).1);
+    }
+
+    #[test]
+    fn test_pawn_table() {
+        let mut pawns = PawnTable::new();
+        let chessboard = Chessboard::from_fen("r1bqkbnr/pp1ppppp/2n5/2p5/4P3/5N2/PPPP1PPP/RNBQKB1R w KQkq - 2 3").unwrap();
+        let entry = *pawns.probe(&chessboard);
+        assert_eq!(entry, PawnEntry::compute(&chessboard));
+        assert_eq!(entry.key, chessboard.pawn_key());
+        assert_eq!(*pawns.probe(&chessboard), entry);
+
+        // a castled king without its shield is worse off in the middlegame
+        let sheltered = Chessboard::from_fen("r4rk1/5ppp/8/8/8/8/5PPP/R4RK1 w - - 0 1").unwrap();
+        let exposed = Chessboard::from_fen("r4rk1/5ppp/8/8/8/5PPP/8/R4RK1 w - - 0 1").unwrap();
+        assert_eq!(pawns.evaluate(&sheltered).0, 0);
+        assert!(pawns.evaluate(&exposed).0 < 0);
+    }
+}
@@ -0,0 +1,77 @@
+#[cfg(test)]
+mod tests {
+    use lib::engine::models::{board::{Chessboard, Color, Square}, fen::{FenError, FenField}, r#move::Move};
+
+    #[test]
+    fn test_fen_round_trip() {
+        let fens = [
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+            "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+            "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1",
+            "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1",
+            "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8",
+            "8/2p5/3p4/KP5r/1R3pPk/8/4P3/8 b - g3 0 1",
+            "r4rk1/1pp1qppp/p1np1n2/2b1p1B1/2B1P1b1/P1NP1N2/1PP1QPPP/R4RK1 w - - 0 10",
+        ];
+
+        for fen in fens {
+            assert_eq!(Chessboard::from_fen(fen).unwrap().to_fen(), fen);
+        }
+    }
+
+    #[test]
+    fn test_fen_after_moves() {
+        let mut chessboard = Chessboard::new();
+        let mut played = Vec::new();
+        for uci in ["e2e4", "c7c5", "g1f3"] {
+            let mv = Move::decode_uci(uci, &mut chessboard).unwrap();
+            chessboard.make(&mv);
+            played.push(mv);
+        }
+        assert_eq!(chessboard.to_fen(), "rnbqkbnr/pp1ppppp/8/2p5/4P3/5N2/PPPP1PPP/RNBQKB1R b KQkq - 1 2");
+
+        for mv in played.iter().rev() {
+            chessboard.unmake(mv);
+        }
+        assert_eq!(chessboard.to_fen(), "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1");
+    }
+
+    #[test]
+    fn test_fen_syntax_errors() {
+        let cases = [
+            ("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0", FenError::FieldCount { found: 5 }),
+            ("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP w KQkq - 0 1", FenError::RankCount { found: 7 }),
+            ("rnbqkbnr/ppppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1", FenError::RankLength { rank: 7, index: 9, squares: 9 }),
+            ("rnbqkbnr/pppppppp/7/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1", FenError::RankLength { rank: 6, index: 18, squares: 7 }),
+            ("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNX w KQkq - 0 1", FenError::InvalidCharacter { field: FenField::Placement, index: 42, character: 'X' }),
+            ("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR x KQkq - 0 1", FenError::InvalidValue { field: FenField::SideToMove, index: 44, value: "x".to_owned() }),
+            ("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkx - 0 1", FenError::InvalidCharacter { field: FenField::Castling, index: 49, character: 'x' }),
+            ("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq z9 0 1", FenError::InvalidValue { field: FenField::EnPassant, index: 51, value: "z9".to_owned() }),
+            ("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - a 1", FenError::InvalidValue { field: FenField::HalfMoveClock, index: 53, value: "a".to_owned() }),
+        ];
+
+        for (fen, error) in cases {
+            assert_eq!(Chessboard::from_fen(fen).err(), Some(error), "{}", fen);
+        }
+    }
+
+    #[test]
+    fn test_fen_illegal_positions() {
+        let cases = [
+            ("rnbq1bnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQ - 0 1", FenError::KingCount { color: Color::Black, found: 0 }),
+            ("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBKKBNR w kq - 0 1", FenError::KingCount { color: Color::White, found: 2 }),
+            ("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNp w Qkq - 0 1", FenError::PawnOnBackRank { square: Square::H1 }),
+            ("4k3/8/8/8/8/8/8/r3K3 b - - 0 1", FenError::OpponentInCheck { color: Color::White }),
+            ("4k3/8/8/8/8/8/8/R3K3 w - e6 0 1", FenError::ImpossibleEnPassant { square: Square::E6 }),
+            ("4k3/8/8/4p3/8/8/8/R3K3 w - e3 0 1", FenError::ImpossibleEnPassant { square: Square::E3 }),
+            ("4k3/8/8/8/8/8/8/R3K3 w K - 0 1", FenError::InvalidCastlingRight { right: 'K' }),
+            ("4k2r/8/8/8/8/8/8/4K3 w q - 0 1", FenError::InvalidCastlingRight { right: 'q' }),
+        ];
+
+        for (fen, error) in cases {
+            assert_eq!(Chessboard::from_fen(fen).err(), Some(error), "{}", fen);
+        }
+
+        assert!(Chessboard::from_fen("4k3/8/8/4p3/8/8/8/R3K3 w Q e6 0 1").is_ok());
+    }
+}
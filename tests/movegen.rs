@@ -0,0 +1,32 @@
+#[cfg(test)]
+mod tests {
+    use lib::engine::{models::board::Chessboard, movegen::{generate_legal_captures, generate_legal_moves}};
+
+    #[test]
+    fn test_captures_mode() {
+        let fens = [
+            "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+            "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8",
+            "rnbqkbnr/ppp1p1pp/8/3pPp2/8/8/PPPP1PPP/RNBQKBNR w KQkq f6 0 3",
+            "2bn1k2/3P4/8/8/8/8/8/7K w - - 0 1",
+        ];
+
+        for fen in fens {
+            let mut chessboard = Chessboard::from_fen(fen).unwrap();
+            let mut expected: Vec<String> = generate_legal_moves(&mut chessboard)
+                .iter()
+                .map(|mv| mv.to_san(&mut chessboard))
+                .filter(|san| san.contains('x') || san.contains('='))
+                .collect();
+            let mut captures: Vec<String> = generate_legal_captures(&mut chessboard)
+                .iter()
+                .map(|mv| mv.to_san(&mut chessboard))
+                .collect();
+
+            expected.sort();
+            captures.sort();
+            assert!(!captures.is_empty(), "{}", fen);
+            assert_eq!(captures, expected, "{}", fen);
+        }
+    }
+}
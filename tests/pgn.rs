@@ -0,0 +1,116 @@
+#[cfg(test)]
+mod tests {
+    use lib::engine::{models::{board::Chessboard, r#move::{Move, MoveError}}, notation::pgn::{Game, GameResult, PgnError}};
+
+    const IMMORTAL_GAME: &str = r#"[Event "London"]
+[Site "London ENG"]
+[Date "1851.06.21"]
+[Round "?"]
+[White "Adolf Anderssen"]
+[Black "Lionel Kieseritzky"]
+[Result "1-0"]
+
+1.e4 e5 2.f4 exf4 3.Bc4 Qh4+ 4.Kf1 b5 5.Bxb5 Nf6 6.Nf3 Qh6 7.d3 Nh5 8.Nh4 Qg5
+9.Nf5 c6 10.g4 Nf6 11.Rg1 cxb5 12.h4 Qg6 13.h5 Qg5 14.Qf3 Ng8 15.Bxf4 Qf6
+16.Nc3 Bc5 17.Nd5 Qxb2 18.Bd6 Bxg1 {It is from this move that Black's defeat
+stems.} 19.e5 Qxa1+ 20.Ke2 Na6 21.Nxg7+ Kd8 22.Qf6+ Nxf6 23.Be7# 1-0
+"#;
+
+    #[test]
+    fn test_read_game() {
+        let game = Game::from_pgn(IMMORTAL_GAME).unwrap();
+
+        assert_eq!(game.tag("White"), Some("Adolf Anderssen"));
+        assert_eq!(game.result, GameResult::WhiteWins);
+        assert_eq!(game.moves.len(), 45);
+        assert_eq!(game.moves[35].comment.as_deref(), Some("It is from this move that Black's defeat stems."));
+        assert_eq!(game.moves.last().unwrap().san, "Be7#");
+
+        let mut chessboard = game.replay().unwrap();
+        assert_eq!(chessboard.to_fen(), "r1bk3r/p2pBpNp/n4n2/1p1NP2P/6P1/3P4/P1P1K3/q5b1 b - - 1 23");
+        assert!(chessboard.outcome().is_over());
+        assert_eq!(game.positions().unwrap().len(), 46);
+    }
+
+    #[test]
+    fn test_read_annotations_and_variations() {
+        let pgn = "[FEN \"4k3/8/8/8/8/8/4P3/4K3 w - - 0 1\"]\n\n{Endgame} 1. e4! $14 (1. e3 Kd7 (1... Ke7) 2. Kd2) 1... Kd7 ; rest of line\n2. Kd2 *";
+        let game = Game::from_pgn(pgn).unwrap();
+
+        assert_eq!(game.comment.as_deref(), Some("Endgame"));
+        assert_eq!(game.result, GameResult::Unknown);
+        assert_eq!(game.moves.len(), 3);
+        assert_eq!(game.moves[0].nags, vec![1, 14]);
+        assert_eq!(game.moves[0].variations.len(), 1);
+        assert_eq!(game.moves[0].variations[0].len(), 3);
+        assert_eq!(game.moves[0].variations[0][1].variations[0][0].san, "Ke7");
+        assert_eq!(game.moves[1].comment.as_deref(), Some("rest of line"));
+    }
+
+    #[test]
+    fn test_annotations_before_first_move() {
+        let pgn = "[FEN \"4k3/8/8/3pP3/8/8/8/4K3 w - d6 0 1\"]\n\n$10 1. exd6 e.p. ({Or} $146 1. Kd2 Kd7) 1... Kd7 *";
+        let game = Game::from_pgn(pgn).unwrap();
+
+        assert_eq!(game.moves[0].nags_before, vec![10]);
+        assert_eq!(game.moves[0].san, "exd6");
+        let variation = &game.moves[0].variations[0];
+        assert_eq!(variation[0].comment_before.as_deref(), Some("Or"));
+        assert_eq!(variation[0].nags_before, vec![146]);
+
+        let written = game.to_pgn();
+        assert!(written.ends_with("\n\n$10 1. exd6 ({Or} $146 1. Kd2 Kd7) 1... Kd7 *\n"));
+        assert_eq!(Game::from_pgn(&written).unwrap().moves, game.moves);
+    }
+
+    #[test]
+    fn test_read_text_around_games() {
+        let pgn = "% exported by some tool\n[Event \"x\"]\n\n1. e4 e5 1-0 {game over} $1\n\n[Event \"y\"]\n\n1. d4 *\n{trailing}";
+        let games = Game::parse_all(pgn).unwrap();
+
+        assert_eq!(games.len(), 2);
+        assert_eq!(games[0].result, GameResult::WhiteWins);
+        assert_eq!(games[0].moves.len(), 2);
+        assert_eq!(games[1].tag("Event"), Some("y"));
+        assert_eq!(games[1].moves.len(), 1);
+    }
+
+    #[test]
+    fn test_read_errors() {
+        assert!(matches!(Game::from_pgn("1. e4 e5 2. Ke3 *"), Err(PgnError::InvalidMove { line: 1, error: MoveError::Illegal(_) })));
+        assert_eq!(Game::from_pgn("1. e4 (1. d4 *"), Err(PgnError::UnbalancedVariation { line: 1 }));
+        assert_eq!(Game::from_pgn("[Event \"x\"]\n1. e4 {never closed"), Err(PgnError::Unterminated { line: 2 }));
+        assert_eq!(Game::from_pgn("[Event x]"), Err(PgnError::MalformedTag { line: 1 }));
+    }
+
+    #[test]
+    fn test_write_round_trip() {
+        let game = Game::from_pgn(IMMORTAL_GAME).unwrap();
+        let pgn = game.to_pgn();
+
+        assert!(pgn.starts_with("[Event \"London\"]\n[Site \"London ENG\"]\n[Date \"1851.06.21\"]"));
+        assert!(pgn.lines().all(|line| line.len() <= 80));
+        let movetext = pgn.split_whitespace().collect::<Vec<_>>().join(" ");
+        assert!(movetext.contains("18. Bd6 Bxg1 {It is from this move that Black's defeat stems.} 19. e5"));
+        assert_eq!(Game::from_pgn(&pgn).unwrap(), game);
+    }
+
+    #[test]
+    fn test_write_from_moves() {
+        let mut chessboard = Chessboard::new();
+        let mut moves = Vec::new();
+        for san in ["f3", "e5", "g4", "Qh4#"] {
+            let mv = Move::from_san(san, &mut chessboard).unwrap();
+            chessboard.make(&mv);
+            moves.push(mv);
+        }
+        for mv in moves.iter().rev() {
+            chessboard.unmake(mv);
+        }
+
+        let game = Game::from_moves(&mut chessboard, &moves);
+        assert_eq!(game.result, GameResult::BlackWins);
+        assert!(game.to_pgn().ends_with("\n\n1. f3 e5 2. g4 Qh4# 0-1\n"));
+        assert_eq!(chessboard.to_fen(), Chessboard::new().to_fen());
+    }
+}
@@ -0,0 +1,78 @@
+#[cfg(test)]
+mod tests {
+    use lib::engine::{models::{board::Chessboard, r#move::{Move, MoveError}}, movegen::generate_legal_moves};
+
+    fn san_of(fen: &str, uci: &str) -> String {
+        let mut chessboard = Chessboard::from_fen(fen).unwrap();
+        let mv = generate_legal_moves(&mut chessboard).into_iter().find(|mv| mv.to_string() == uci).unwrap();
+        mv.to_san(&mut chessboard)
+    }
+
+    #[test]
+    fn test_to_san() {
+        assert_eq!(san_of("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1", "g1f3"), "Nf3");
+        assert_eq!(san_of("rnbqkbnr/pppp1ppp/8/4p3/6P1/5P2/PPPPP2P/RNBQKBNR b KQkq - 0 2", "d8h4"), "Qh4#");
+        assert_eq!(san_of("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1", "e1c1"), "O-O-O");
+        assert_eq!(san_of("r3k2r/8/8/8/8/8/8/R3K2R b KQkq - 0 1", "e8g8"), "O-O");
+        assert_eq!(san_of("4k3/8/8/3pP3/8/8/8/4K3 w - d6 0 1", "e5d6"), "exd6");
+        assert_eq!(san_of("k7/4P3/8/8/8/8/8/4K3 w - - 0 1", "e7e8q"), "e8=Q+");
+        assert_eq!(san_of("3r4/4P3/8/8/8/8/8/k3K3 w - - 0 1", "e7d8n"), "exd8=N");
+    }
+
+    #[test]
+    fn test_to_san_disambiguation() {
+        // knights on b8 and f6 can both reach d7
+        assert_eq!(san_of("rn2k3/8/5n2/8/8/8/8/4K3 b - - 0 1", "b8d7"), "Nbd7");
+        // rooks on a1 and a5 can both reach a3
+        assert_eq!(san_of("4k3/8/8/R7/8/8/8/R3K3 w - - 0 1", "a1a3"), "R1a3");
+        // queens on e1, h1 and h4 can all reach e4, e1 shares the rank of h1 and h4 its file
+        assert_eq!(san_of("8/8/k7/8/7Q/8/8/4Q1KQ w - - 0 1", "h1e4"), "Qh1e4");
+    }
+
+    #[test]
+    fn test_from_san() {
+        let mut chessboard = Chessboard::new();
+        for san in ["e4", "e5", "Nf3", "Nc6", "Bb5", "a6", "Bxc6", "dxc6", "O-O", "Qd6"] {
+            let mv = Move::from_san(san, &mut chessboard).unwrap();
+            chessboard.make(&mv);
+        }
+        assert_eq!(chessboard.to_fen(), "r1b1kbnr/1pp2ppp/p1pq4/4p3/4P3/5N2/PPPP1PPP/RNBQ1RK1 w kq - 2 6");
+
+        let mut chessboard = Chessboard::from_fen("4k3/8/8/3pP3/8/8/8/4K3 w - d6 0 1").unwrap();
+        let en_passant = Move::from_san("exd6 e.p.", &mut chessboard).unwrap();
+        assert_eq!(en_passant.to_string(), "e5d6");
+        // the suffix is read but not written back
+        assert_eq!(Move::from_san("exd6e.p.", &mut chessboard), Ok(en_passant.clone()));
+        assert_eq!(en_passant.to_san(&mut chessboard), "exd6");
+
+        let mut chessboard = Chessboard::from_fen("3r4/4P3/8/8/8/8/8/k3K3 w - - 0 1").unwrap();
+        assert_eq!(Move::from_san("exd8=N", &mut chessboard).unwrap().to_string(), "e7d8n");
+        assert_eq!(Move::from_san("e8Q+", &mut chessboard).unwrap().to_string(), "e7e8q");
+    }
+
+    #[test]
+    fn test_from_san_errors() {
+        let mut chessboard = Chessboard::from_fen("rn2k3/8/5n2/8/8/8/8/4K3 b - - 0 1").unwrap();
+        assert_eq!(Move::from_san("Nd7", &mut chessboard), Err(MoveError::Ambiguous("Nd7".to_owned())));
+        assert_eq!(Move::from_san("Nd6", &mut chessboard), Err(MoveError::Illegal("Nd6".to_owned())));
+        assert_eq!(Move::from_san("Zz9", &mut chessboard), Err(MoveError::Malformed("Zz9".to_owned())));
+    }
+
+    #[test]
+    fn test_san_round_trip() {
+        let fens = [
+            "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+            "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1",
+            "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8",
+            "8/2p5/3p4/KP5r/1R3pPk/8/4P3/8 b - g3 0 1",
+        ];
+        for fen in fens {
+            let mut chessboard = Chessboard::from_fen(fen).unwrap();
+            for mv in generate_legal_moves(&mut chessboard) {
+                let san = mv.to_san(&mut chessboard);
+                assert_eq!(Move::from_san(&san, &mut chessboard), Ok(mv), "{} in {}", san, fen);
+            }
+            assert_eq!(chessboard.to_fen(), fen);
+        }
+    }
+}
@@ -0,0 +1,304 @@
+#[cfg(test)]
+mod tests {
+    use std::{sync::atomic::Ordering, thread, time::{Duration, Instant}};
+
+    use lib::engine::{models::board::{Chessboard, Color}, movegen::generate_legal_moves, search::{MATE_IN_MAX_PLY, Search, evaluation::MATE, selectivity::Selectivity, limits::SearchLimits, ordering::MoveOrdering, tt::{NodeType, TTEntry, TranspositionTable, score_from_tt, score_to_tt}, time::TimeManager, uci_score}};
+
+    #[test]
+    fn test_think_finds_mate_in_one() {
+        let mut chessboard = Chessboard::from_fen("6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1").unwrap();
+        let mut search = Search::new(3);
+
+        let result = search.think(&mut chessboard);
+        assert_eq!(result.best_move.as_ref().unwrap().to_string(), "a1a8");
+        assert_eq!(result.score, MATE - 1);
+        assert_eq!(result.pv, vec![result.best_move.clone().unwrap()]);
+        assert_eq!(result.ponder_move, None);
+        assert_eq!(result.depth, 3);
+        assert_eq!(chessboard.to_fen(), "6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1");
+    }
+
+    #[test]
+    fn test_think_can_be_interrupted() {
+        let mut chessboard = Chessboard::new();
+        let mut search = Search::new(64);
+        let stop = search.stop_flag();
+
+        let start = Instant::now();
+        let stopper = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(300));
+            stop.store(true, Ordering::Relaxed);
+        });
+        let best_move = search.think(&mut chessboard).best_move.unwrap();
+        stopper.join().unwrap();
+
+        assert!(start.elapsed() < Duration::from_secs(5));
+        assert!(generate_legal_moves(&mut chessboard).contains(&best_move));
+        assert_eq!(chessboard.to_fen(), Chessboard::new().to_fen());
+    }
+
+    #[test]
+    fn test_parse_go_limits() {
+        let limits = SearchLimits::parse("go wtime 300000 btime -20 winc 2000 binc 2000 movestogo 12 depth 9 nodes 5000 ponder");
+        assert_eq!(limits.wtime, Some(Duration::from_millis(300000)));
+        assert_eq!(limits.btime, Some(Duration::ZERO));
+        assert_eq!(limits.winc, Some(Duration::from_secs(2)));
+        assert_eq!(limits.binc, Some(Duration::from_secs(2)));
+        assert_eq!(limits.movestogo, Some(12));
+        assert_eq!(limits.depth, Some(9));
+        assert_eq!(limits.nodes, Some(5000));
+        assert!(!limits.infinite);
+        assert!(limits.ponder);
+
+        let limits = SearchLimits::parse("go movetime abc infinite");
+        assert_eq!(limits, SearchLimits { infinite: true, ..Default::default() });
+        assert!(!SearchLimits::parse("go").is_timed());
+    }
+
+    #[test]
+    fn test_time_manager_deadlines() {
+        let limits = SearchLimits::parse("go wtime 60000 btime 20");
+        let overhead = Duration::from_millis(30);
+
+        let white = TimeManager::new(&limits, Color::White, overhead);
+        assert!(!white.soft_deadline_reached());
+        assert!(!white.hard_deadline_reached());
+
+        // black's clock is already below the move overhead
+        let black = TimeManager::new(&limits, Color::Black, overhead);
+        assert!(black.soft_deadline_reached());
+        assert!(black.hard_deadline_reached());
+
+        let infinite = TimeManager::new(&SearchLimits::parse("go infinite"), Color::Black, overhead);
+        assert!(!infinite.hard_deadline_reached());
+    }
+
+    #[test]
+    fn test_time_manager_moves_to_go() {
+        let overhead = Duration::from_millis(30);
+        let budget = |go: &str| TimeManager::new(&SearchLimits::parse(go), Color::White, overhead).soft_budget().unwrap();
+
+        // the time is split over every move the GUI says is left, 30 only being assumed when it says nothing
+        assert!(budget("go wtime 60000 btime 60000 movestogo 40") < budget("go wtime 60000 btime 60000 movestogo 30"));
+        assert_eq!(budget("go wtime 60000 btime 60000"), budget("go wtime 60000 btime 60000 movestogo 30"));
+        assert!(budget("go wtime 60000 btime 60000 movestogo 0") <= Duration::from_millis(48000));
+    }
+
+    #[test]
+    fn test_think_respects_movetime() {
+        let mut chessboard = Chessboard::new();
+        let mut search = Search::new(3);
+        search.limits = SearchLimits::parse("go movetime 300");
+
+        let start = Instant::now();
+        let best_move = search.think(&mut chessboard).best_move.unwrap();
+        assert!(start.elapsed() < Duration::from_secs(2));
+        assert!(generate_legal_moves(&mut chessboard).contains(&best_move));
+    }
+
+    #[test]
+    fn test_think_respects_node_limit() {
+        let mut chessboard = Chessboard::new();
+        let mut search = Search::new(3);
+        search.limits = SearchLimits::parse("go nodes 2000");
+
+        let start = Instant::now();
+        assert!(search.think(&mut chessboard).best_move.is_some());
+        assert!(start.elapsed() < Duration::from_secs(2));
+    }
+
+    #[test]
+    fn test_think_waits_for_ponderhit() {
+        let mut chessboard = Chessboard::new();
+        let mut search = Search::new(3);
+        search.limits = SearchLimits::parse("go ponder movetime 100");
+        let pondering = search.ponder_flag();
+        pondering.store(true, Ordering::Relaxed);
+
+        let start = Instant::now();
+        let ponderhit = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(500));
+            pondering.store(false, Ordering::Relaxed);
+        });
+        let result = search.think(&mut chessboard);
+        ponderhit.join().unwrap();
+
+        // the clock only starts on ponderhit
+        assert!(start.elapsed() >= Duration::from_millis(500));
+        assert!(start.elapsed() < Duration::from_secs(3));
+
+        let best_move = result.best_move.unwrap();
+        chessboard.make(&best_move);
+        assert!(generate_legal_moves(&mut chessboard).contains(&result.ponder_move.unwrap()));
+    }
+
+    #[test]
+    fn test_uci_score() {
+        assert_eq!(uci_score(35), "cp 35");
+        assert_eq!(uci_score(-120), "cp -120");
+        // mate in 2 for us: our move, their reply, our mating move
+        assert_eq!(uci_score(MATE - 3), "mate 2");
+        assert_eq!(uci_score(MATE - 1), "mate 1");
+        // mated in 1 after our move and their mating reply
+        assert_eq!(uci_score(-MATE + 2), "mate -1");
+    }
+
+    #[test]
+    fn test_think_counts_nodes() {
+        let mut chessboard = Chessboard::new();
+        let mut search = Search::new(3);
+        search.think(&mut chessboard).best_move.unwrap();
+
+        assert!(search.nodes() > 400);
+        assert!(search.hashfull() <= 1000);
+    }
+
+    #[test]
+    fn test_think_principal_variation() {
+        let mut chessboard = Chessboard::new();
+        let mut search = Search::new(4);
+        let result = search.think(&mut chessboard);
+
+        assert_eq!(result.depth, 4);
+        // the quiescence search may extend the line with captures
+        assert!(result.pv.len() >= 4);
+        assert_eq!(result.pv.first(), result.best_move.as_ref());
+        assert_eq!(result.pv.get(1), result.ponder_move.as_ref());
+
+        // every move of the line is legal in turn
+        for mv in &result.pv {
+            assert!(generate_legal_moves(&mut chessboard).contains(mv));
+            chessboard.make(mv);
+        }
+    }
+
+    #[test]
+    fn test_quiescence_sees_recapture() {
+        // Qxd5 wins a pawn at depth 1, until exd5 takes the queen back
+        let mut chessboard = Chessboard::from_fen("4k3/8/4p3/3p4/8/8/8/3QK3 w - - 0 1").unwrap();
+        let mut search = Search::new(1);
+
+        let result = search.think(&mut chessboard);
+        assert_ne!(result.best_move.unwrap().to_string(), "d1d5");
+        assert_eq!(chessboard.to_fen(), "4k3/8/4p3/3p4/8/8/8/3QK3 w - - 0 1");
+    }
+
+    #[test]
+    fn test_move_ordering() {
+        let mut chessboard = Chessboard::from_fen("4k3/8/8/3q1n2/4P3/8/8/3QK3 w - - 0 1").unwrap();
+        let moves = generate_legal_moves(&mut chessboard);
+        let tt_move = moves.iter().find(|mv| mv.to_string() == "d1a4").cloned();
+
+        let ordered: Vec<String> = MoveOrdering::new()
+            .picker(moves, tt_move.as_ref(), Color::White, 0)
+            .map(|mv| mv.to_string())
+            .collect();
+        // hash move, then captures from the most valuable victim and the least valuable attacker
+        assert_eq!(ordered[..4], ["d1a4", "e4d5", "d1d5", "e4f5"]);
+    }
+
+    #[test]
+    fn test_transposition_table() {
+        let tt = TranspositionTable::new(1);
+        let entry = TTEntry { flag: NodeType::Upperbound, depth: 7, value: -9995, best_move: 0x1234 };
+
+        assert_eq!(tt.probe(42), None);
+        tt.new_search();
+        tt.store(42, entry);
+        assert_eq!(tt.probe(42), Some(entry));
+        assert_eq!(tt.probe(43), None);
+
+        // a new entry of the same position without a move keeps the previous one
+        tt.store(42, TTEntry { flag: NodeType::Exact, depth: 8, value: 12, best_move: 0 });
+        assert_eq!(tt.probe(42).map(|entry| entry.best_move), Some(0x1234));
+
+        for key in 0..100_000u64 {
+            tt.store(key.wrapping_mul(0x9E37_79B9_7F4A_7C15), TTEntry { flag: NodeType::Exact, depth: 1, value: 0, best_move: 0 });
+        }
+        assert!(tt.hashfull() > 900);
+        tt.clear();
+        assert_eq!(tt.hashfull(), 0);
+        assert_eq!(tt.probe(42), None);
+    }
+
+    #[test]
+    fn test_think_with_helper_threads() {
+        let mut chessboard = Chessboard::from_fen("6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1").unwrap();
+        let mut search = Search::new(3);
+        search.threads = 4;
+
+        let result = search.think(&mut chessboard);
+        assert_eq!(result.best_move.unwrap().to_string(), "a1a8");
+        assert_eq!(result.depth, 3);
+        assert_eq!(chessboard.to_fen(), "6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1");
+
+        // the helpers count their nodes too
+        let mut single = Search::new(3);
+        single.think(&mut chessboard);
+        assert!(search.nodes() > single.nodes());
+    }
+
+    #[test]
+    fn test_think_within_aspiration_windows() {
+        let mut chessboard = Chessboard::from_fen("8/8/8/4k3/8/8/8/4K2Q w - - 0 1").unwrap();
+        let mut search = Search::new(6);
+
+        // iterations from depth 4 are searched within a window around the previous score
+        let result = search.think(&mut chessboard);
+        assert_eq!(result.depth, 6);
+        assert!(result.score > 0 && result.score < MATE_IN_MAX_PLY, "{}", result.score);
+        assert_eq!(result.pv.first(), result.best_move.as_ref());
+    }
+
+    #[test]
+    fn test_selectivity_switches() {
+        let fen = "r1bqkb1r/pppp1ppp/2n2n2/4p3/2B1P3/5N2/PPPP1PPP/RNBQK2R w KQkq - 4 4";
+        let mut full = Search::new(5);
+        full.selectivity = Selectivity {
+            null_move: false,
+            late_move_reductions: false,
+            futility: false,
+            reverse_futility: false,
+            late_move_pruning: false,
+        };
+        full.think(&mut Chessboard::from_fen(fen).unwrap());
+
+        let mut selective = Search::new(5);
+        let mut chessboard = Chessboard::from_fen(fen).unwrap();
+        let result = selective.think(&mut chessboard);
+        assert_eq!(result.depth, 5);
+        assert_eq!(chessboard.to_fen(), fen);
+        assert!(selective.nodes() < full.nodes(), "{} >= {}", selective.nodes(), full.nodes());
+
+        // the pruning never misses a mate in one
+        let mut chessboard = Chessboard::from_fen("6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1").unwrap();
+        assert_eq!(selective.think(&mut chessboard).best_move.unwrap().to_string(), "a1a8");
+    }
+
+    #[test]
+    fn test_check_extension() {
+        // Qg8+ Rxg8 Nf7#, the mate is one ply beyond depth 2 but every white move checks
+        let mut chessboard = Chessboard::from_fen("3r3k/6pp/7N/8/2Q5/8/8/6K1 w - - 0 1").unwrap();
+        let mut search = Search::new(2);
+
+        let result = search.think(&mut chessboard);
+        assert_eq!(result.best_move.unwrap().to_string(), "c4g8");
+        assert_eq!(uci_score(result.score), "mate 2");
+    }
+
+    #[test]
+    fn test_mate_and_stalemate_scores() {
+        // Qf7 stalemates, Qg7 mates
+        let mut chessboard = Chessboard::from_fen("7k/4Q3/5K2/8/8/8/8/8 w - - 0 1").unwrap();
+        let mut search = Search::new(3);
+
+        let result = search.think(&mut chessboard);
+        assert_eq!(result.best_move.unwrap().to_string(), "e7g7");
+        assert_eq!(result.score, MATE - 1);
+
+        // mates are stored relative to the position, and read back relative to the root
+        assert_eq!(score_from_tt(score_to_tt(MATE - 5, 3), 1), MATE - 3);
+        assert_eq!(score_from_tt(score_to_tt(-MATE + 4, 4), 2), -MATE + 2);
+        assert_eq!(score_from_tt(score_to_tt(150, 4), 2), 150);
+    }
+}
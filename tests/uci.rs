@@ -0,0 +1,35 @@
+#[cfg(test)]
+mod tests {
+    use lib::engine::models::{board::Chessboard, r#move::{Move, MoveError}};
+
+    #[test]
+    fn test_decode_special_moves() {
+        let mut chessboard = Chessboard::from_fen("r3k2r/1P6/8/3pP3/8/8/8/R3K2R w KQkq d6 0 1").unwrap();
+
+        let en_passant = Move::decode_uci("e5d6", &mut chessboard).unwrap();
+        assert_eq!(en_passant.to_san(&mut chessboard), "exd6");
+        chessboard.make(&en_passant);
+        assert_eq!(chessboard.to_fen(), "r3k2r/1P6/3P4/8/8/8/8/R3K2R b KQkq - 0 1");
+        chessboard.unmake(&en_passant);
+
+        assert_eq!(Move::decode_uci("e1g1", &mut chessboard).unwrap().to_san(&mut chessboard), "O-O");
+        assert_eq!(Move::decode_uci("e1c1", &mut chessboard).unwrap().to_san(&mut chessboard), "O-O-O");
+        assert_eq!(Move::decode_uci("b7a8n", &mut chessboard).unwrap().to_san(&mut chessboard), "bxa8=N");
+        assert_eq!(Move::decode_uci("b7b8q", &mut chessboard).unwrap().to_string(), "b7b8q");
+        assert_eq!(chessboard.to_fen(), "r3k2r/1P6/8/3pP3/8/8/8/R3K2R w KQkq d6 0 1");
+    }
+
+    #[test]
+    fn test_decode_errors() {
+        let mut chessboard = Chessboard::from_fen("4k3/1P6/8/8/8/8/4r3/4K2R w K - 0 1").unwrap();
+
+        assert_eq!(Move::decode_uci("b7b8x", &mut chessboard), Err(MoveError::Malformed("b7b8x".to_owned())));
+        assert_eq!(Move::decode_uci("e1", &mut chessboard), Err(MoveError::Malformed("e1".to_owned())));
+        assert_eq!(Move::decode_uci("i1e2", &mut chessboard), Err(MoveError::Malformed("i1e2".to_owned())));
+        assert_eq!(Move::decode_uci("b7b8", &mut chessboard), Err(MoveError::Illegal("b7b8".to_owned())));
+        assert_eq!(Move::decode_uci("e1g1", &mut chessboard), Err(MoveError::Illegal("e1g1".to_owned())));
+        assert_eq!(Move::decode_uci("a2a4", &mut chessboard), Err(MoveError::Illegal("a2a4".to_owned())));
+        assert_eq!(Move::decode_uci("h1h8", &mut chessboard), Err(MoveError::Illegal("h1h8".to_owned())));
+        assert!(Move::decode_uci("e1e2", &mut chessboard).is_ok());
+    }
+}
@@ -0,0 +1,132 @@
+#[cfg(test)]
+mod tests {
+    use lib::engine::models::{board::Chessboard, r#move::Move, zobrist::PolyglotKeys};
+
+    fn play(chessboard: &mut Chessboard, moves: &[&str]) -> Vec<Move> {
+        let mut played = Vec::new();
+        for uci in moves {
+            let mv = Move::decode_uci(uci, chessboard).unwrap();
+            chessboard.make(&mv);
+            played.push(mv);
+        }
+        played
+    }
+
+    #[test]
+    fn test_zobrist_matches_fen() {
+        let mut chessboard = Chessboard::new();
+        play(&mut chessboard, &["e2e4", "c7c5", "g1f3"]);
+
+        let fen = "rnbqkbnr/pp1ppppp/8/2p5/4P3/5N2/PPPP1PPP/RNBQKB1R b KQkq - 1 2";
+        assert_eq!(chessboard.zobrist_key(), Chessboard::from_fen(fen).unwrap().zobrist_key());
+    }
+
+    #[test]
+    fn test_zobrist_transposition() {
+        let mut first = Chessboard::new();
+        play(&mut first, &["g1f3", "g8f6", "b1c3", "b8c6"]);
+
+        let mut second = Chessboard::new();
+        play(&mut second, &["b1c3", "b8c6", "g1f3", "g8f6"]);
+
+        assert_eq!(first.zobrist_key(), second.zobrist_key());
+    }
+
+    #[test]
+    fn test_zobrist_en_passant_square() {
+        let capturable = Chessboard::from_fen("rnbqkbnr/ppp1pppp/8/8/3pP3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1").unwrap();
+        let no_en_passant = Chessboard::from_fen("rnbqkbnr/ppp1pppp/8/8/3pP3/8/PPPP1PPP/RNBQKBNR b KQkq - 0 1").unwrap();
+        assert_ne!(capturable.zobrist_key(), no_en_passant.zobrist_key());
+
+        // nobody can take on e3, it is the same position
+        let double_push = Chessboard::from_fen("rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1").unwrap();
+        let no_en_passant = Chessboard::from_fen("rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq - 0 1").unwrap();
+        assert_eq!(double_push.zobrist_key(), no_en_passant.zobrist_key());
+    }
+
+    #[test]
+    fn test_zobrist_unmake_restores_key() {
+        let mut chessboard = Chessboard::new();
+        let start = chessboard.zobrist_key();
+
+        let moves = play(&mut chessboard, &["e2e4", "e7e5", "g1f3", "g8f6", "f1e2", "f8e7", "e1g1", "e8g8"]);
+        assert_ne!(chessboard.zobrist_key(), start);
+
+        for mv in moves.iter().rev() {
+            chessboard.unmake(mv);
+        }
+        assert_eq!(chessboard.zobrist_key(), start);
+    }
+
+    #[test]
+    fn test_zobrist_null_move() {
+        let mut chessboard = Chessboard::new();
+        play(&mut chessboard, &["e2e4", "d7d5", "e4e5", "f7f5"]);
+        let fen = chessboard.to_fen();
+        let key = chessboard.zobrist_key();
+
+        // passing the turn loses the en passant capture
+        chessboard.make_null();
+        let expected = "rnbqkbnr/ppp1p1pp/8/3pPp2/8/8/PPPP1PPP/RNBQKBNR b KQkq - 0 3";
+        assert_eq!(chessboard.zobrist_key(), Chessboard::from_fen(expected).unwrap().zobrist_key());
+
+        chessboard.unmake_null();
+        assert_eq!(chessboard.to_fen(), fen);
+        assert_eq!(chessboard.zobrist_key(), key);
+    }
+
+    #[test]
+    fn test_zobrist_is_deterministic() {
+        // the tables are generated at compile time from a fixed seed, so this value must never change between runs
+        assert_eq!(Chessboard::new().zobrist_key(), Chessboard::new().zobrist_key());
+        assert_eq!(Chessboard::new().zobrist_key(), 0x9b56_3810_011e_50b8);
+    }
+
+    #[test]
+    fn test_polyglot_layout() {
+        let mut random64 = [0u64; 781];
+        for (i, value) in random64.iter_mut().enumerate() {
+            *value = 1u64.rotate_left(i as u32) ^ (i as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15);
+        }
+        let keys = PolyglotKeys::new(random64);
+
+        // white king e1 (kind 11), black king e8 (kind 10), white to move
+        let kings = Chessboard::from_fen("4k3/8/8/8/8/8/8/4K3 w - - 0 1").unwrap();
+        assert_eq!(keys.hash(&kings), random64[64 * 11 + 4] ^ random64[64 * 10 + 60] ^ random64[780]);
+
+        // the en passant file is ignored when no pawn can capture on it
+        let no_capture = Chessboard::from_fen("4k3/8/8/8/4P3/8/8/4K3 b - e3 0 1").unwrap();
+        let no_capture_no_ep = Chessboard::from_fen("4k3/8/8/8/4P3/8/8/4K3 b - - 0 1").unwrap();
+        assert_eq!(keys.hash(&no_capture), keys.hash(&no_capture_no_ep));
+
+        let capture = Chessboard::from_fen("4k3/8/8/8/3pP3/8/8/4K3 b - e3 0 1").unwrap();
+        let capture_no_ep = Chessboard::from_fen("4k3/8/8/8/3pP3/8/8/4K3 b - - 0 1").unwrap();
+        assert_eq!(keys.hash(&capture), keys.hash(&capture_no_ep) ^ random64[772 + 4]);
+    }
+
+    #[test]
+    #[ignore = "needs the Polyglot Random64 array in books/random64.json"]
+    fn test_polyglot_reference_keys() {
+        let keys = PolyglotKeys::load(concat!(env!("CARGO_MANIFEST_DIR"), "/books/random64.json")).unwrap();
+
+        // the test positions of the Polyglot book format specification
+        let lines: [(&[&str], u64); 9] = [
+            (&[], 0x463b96181691fc9c),
+            (&["e2e4"], 0x823c9b50fd114196),
+            (&["e2e4", "d7d5"], 0x0756b94461c50fb0),
+            (&["e2e4", "d7d5", "e4e5"], 0x662fafb965db29d4),
+            // en passant on f6, the e5 pawn can take
+            (&["e2e4", "d7d5", "e4e5", "f7f5"], 0x22a48b5a8e47ff78),
+            (&["e2e4", "d7d5", "e4e5", "f7f5", "e1e2"], 0x652a607ca3f242c1),
+            (&["e2e4", "d7d5", "e4e5", "f7f5", "e1e2", "e8f7"], 0x00fdd303c946bdd9),
+            // en passant on c3, the b4 pawn can take
+            (&["a2a4", "b7b5", "h2h4", "b5b4", "c2c4"], 0x3c8123ea7b067637),
+            (&["a2a4", "b7b5", "h2h4", "b5b4", "c2c4", "b4c3", "a1a3"], 0x5c3f9b829b279560),
+        ];
+        for (moves, key) in lines {
+            let mut chessboard = Chessboard::new();
+            play(&mut chessboard, moves);
+            assert_eq!(keys.hash(&chessboard), key, "after {:?}", moves);
+        }
+    }
+}